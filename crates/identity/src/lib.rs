@@ -0,0 +1,8 @@
+use evento::EventDetails;
+use timada_shared::RequestMetadata;
+
+pub mod migrator;
+pub mod token;
+pub mod user;
+
+pub(crate) type RequestEvent<D> = EventDetails<D, RequestMetadata>;
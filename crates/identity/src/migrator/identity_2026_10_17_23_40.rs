@@ -0,0 +1,77 @@
+use crate::user::QueryUserIden;
+use sea_query::{ColumnDef, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+pub struct CreateUserTableOperation;
+
+impl Statements for CreateUserTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(QueryUserIden::Table)
+                .col(
+                    ColumnDef::new(QueryUserIden::Id)
+                        .string()
+                        .string_len(26)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryUserIden::Email)
+                        .string()
+                        .string_len(255)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryUserIden::CreatedAt)
+                        .string()
+                        .string_len(19)
+                        .not_null(),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(QueryUserIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateUserEmailIdxOperation;
+
+impl Statements for CreateUserEmailIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_user_email")
+                .table(QueryUserIden::Table)
+                .col(QueryUserIden::Email)
+                .unique()
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_user_email")
+                .table(QueryUserIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Identity202610172340;
+
+timada_shared::query_migration!(
+    Identity202610172340,
+    "main",
+    "identity_2026_10_17_23_40",
+    vec_box![],
+    vec_box![CreateUserTableOperation, CreateUserEmailIdxOperation]
+);
@@ -0,0 +1,42 @@
+use crate::user::QueryUserIden;
+use sea_query::{ColumnDef, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::identity_2026_10_17_23_40::Identity202610172340;
+
+pub struct AddUserOwnerIdOperation;
+
+impl Statements for AddUserOwnerIdOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryUserIden::Table)
+                .add_column(
+                    ColumnDef::new(QueryUserIden::OwnerId)
+                        .string()
+                        .string_len(26),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryUserIden::Table)
+                .drop_column(QueryUserIden::OwnerId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Identity202610172355;
+
+timada_shared::query_migration!(
+    Identity202610172355,
+    "main",
+    "identity_2026_10_17_23_55",
+    vec_box![Identity202610172340],
+    vec_box![AddUserOwnerIdOperation]
+);
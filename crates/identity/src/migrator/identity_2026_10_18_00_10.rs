@@ -0,0 +1,44 @@
+use crate::user::QueryUserIden;
+use sea_query::{ColumnDef, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::identity_2026_10_17_23_55::Identity202610172355;
+
+pub struct AddUserRolesOperation;
+
+impl Statements for AddUserRolesOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryUserIden::Table)
+                .add_column(
+                    ColumnDef::new(QueryUserIden::Roles)
+                        .string()
+                        .string_len(255)
+                        .not_null()
+                        .default("admin"),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryUserIden::Table)
+                .drop_column(QueryUserIden::Roles)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Identity202610180010;
+
+timada_shared::query_migration!(
+    Identity202610180010,
+    "main",
+    "identity_2026_10_18_00_10",
+    vec_box![Identity202610172355],
+    vec_box![AddUserRolesOperation]
+);
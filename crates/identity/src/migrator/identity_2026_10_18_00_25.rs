@@ -0,0 +1,119 @@
+use crate::token::QueryTokenIden;
+use sea_query::{ColumnDef, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::identity_2026_10_18_00_10::Identity202610180010;
+
+pub struct CreateTokenTableOperation;
+
+impl Statements for CreateTokenTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(QueryTokenIden::Table)
+                .col(
+                    ColumnDef::new(QueryTokenIden::Id)
+                        .string()
+                        .string_len(26)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryTokenIden::UserId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryTokenIden::Name)
+                        .string()
+                        .string_len(50)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryTokenIden::Hash)
+                        .string()
+                        .string_len(64)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryTokenIden::CreatedAt)
+                        .string()
+                        .string_len(19)
+                        .not_null(),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(QueryTokenIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateTokenHashIdxOperation;
+
+impl Statements for CreateTokenHashIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_token_hash")
+                .table(QueryTokenIden::Table)
+                .col(QueryTokenIden::Hash)
+                .unique()
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_token_hash")
+                .table(QueryTokenIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateTokenUserIdIdxOperation;
+
+impl Statements for CreateTokenUserIdIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_token_user_id")
+                .table(QueryTokenIden::Table)
+                .col(QueryTokenIden::UserId)
+                .col(QueryTokenIden::CreatedAt)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_token_user_id")
+                .table(QueryTokenIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Identity202610180025;
+
+timada_shared::query_migration!(
+    Identity202610180025,
+    "main",
+    "identity_2026_10_18_00_25",
+    vec_box![Identity202610180010],
+    vec_box![
+        CreateTokenTableOperation,
+        CreateTokenHashIdxOperation,
+        CreateTokenUserIdIdxOperation
+    ]
+);
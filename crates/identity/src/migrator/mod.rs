@@ -0,0 +1,19 @@
+mod identity_2026_10_17_23_40;
+mod identity_2026_10_17_23_55;
+mod identity_2026_10_18_00_10;
+mod identity_2026_10_18_00_25;
+
+use identity_2026_10_17_23_40::Identity202610172340;
+use identity_2026_10_17_23_55::Identity202610172355;
+use identity_2026_10_18_00_10::Identity202610180010;
+use identity_2026_10_18_00_25::Identity202610180025;
+use timada_shared::QueryMigrator;
+
+pub fn add_migrations(migrator: &mut QueryMigrator) -> Result<(), sqlx_migrator::Error> {
+    migrator.add_migration(Identity202610172340)?;
+    migrator.add_migration(Identity202610172355)?;
+    migrator.add_migration(Identity202610180010)?;
+    migrator.add_migration(Identity202610180025)?;
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use timada_shared::{Conflict, NotFound};
+use validator::Validate;
+
+use crate::token::{Created, Revoked, Token};
+
+const SECRET_PREFIX: &str = "tmd_";
+
+/// Tokens are 256 bit random values, a plain SHA-256 is enough to store them.
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+#[derive(Validate, Deserialize)]
+pub struct CreateInput {
+    #[validate(length(min = 3, max = 50))]
+    pub name: String,
+}
+
+/// Returns the secret along with the token, it cannot be read back afterwards.
+pub fn create(
+    user_id: impl Into<String>,
+    input: CreateInput,
+) -> anyhow::Result<(evento::SaveBuilder<Token>, String)> {
+    input.validate()?;
+
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let secret = format!("{SECRET_PREFIX}{}", hex::encode(bytes));
+
+    let builder = evento::create().data(&Created {
+        user_id: user_id.into(),
+        name: input.name,
+        hash: hash_secret(&secret),
+    })?;
+
+    Ok((builder, secret))
+}
+
+pub async fn revoke<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    user_id: &str,
+) -> anyhow::Result<evento::SaveBuilder<Token>> {
+    let token = evento::load::<Token, _>(executor, id).await?;
+    if token.item.user_id != user_id {
+        anyhow::bail!(NotFound::new("token not found"));
+    }
+
+    if token.item.revoked {
+        anyhow::bail!(Conflict::new("token revoked"));
+    }
+
+    Ok(evento::save_with(token).data(&Revoked {})?)
+}
@@ -0,0 +1,45 @@
+mod command;
+mod query;
+
+use evento::AggregatorName;
+use serde::{Deserialize, Serialize};
+
+pub use command::*;
+pub use query::*;
+
+use crate::RequestEvent;
+
+/// API token of a user, machine clients act with the roles and owner of that user.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Token {
+    pub user_id: String,
+    pub name: String,
+    pub revoked: bool,
+}
+
+#[evento::aggregator]
+impl Token {
+    async fn created(&mut self, event: RequestEvent<Created>) -> anyhow::Result<()> {
+        self.user_id = event.data.user_id;
+        self.name = event.data.name;
+
+        Ok(())
+    }
+
+    async fn revoked(&mut self, _event: RequestEvent<Revoked>) -> anyhow::Result<()> {
+        self.revoked = true;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Created {
+    pub user_id: String,
+    pub name: String,
+    /// SHA-256 of the secret, the secret itself is only shown once.
+    pub hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Revoked {}
@@ -0,0 +1,118 @@
+use crate::{
+    RequestEvent,
+    token::{Created, Revoked, Token, hash_secret},
+};
+use evento::{AggregatorName, SubscribeBuilder};
+use sea_query::{Expr, ExprTrait, Order, Query};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use timada_shared::{QueryPool, Reported, format_created_at};
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryToken {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    #[serde(skip)]
+    pub hash: String,
+    pub created_at: String,
+}
+
+#[evento::handler(Token)]
+async fn tokens_created<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Created>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::insert()
+        .into_table(QueryTokenIden::Table)
+        .columns([
+            QueryTokenIden::Id,
+            QueryTokenIden::UserId,
+            QueryTokenIden::Name,
+            QueryTokenIden::Hash,
+            QueryTokenIden::CreatedAt,
+        ])
+        .values_panic([
+            event.aggregator_id.to_owned().into(),
+            event.data.user_id.to_owned().into(),
+            event.data.name.to_owned().into(),
+            event.data.hash.to_owned().into(),
+            format_created_at(event.timestamp).into(),
+        ])
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Token)]
+async fn tokens_revoked<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Revoked>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(QueryTokenIden::Table)
+        .and_where(Expr::col(QueryTokenIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+/// Tokens of the user that are not revoked, newest first.
+pub async fn query_tokens(pool: &QueryPool, user_id: &str) -> anyhow::Result<Vec<QueryToken>> {
+    let statement = Query::select()
+        .columns([
+            QueryTokenIden::Id,
+            QueryTokenIden::UserId,
+            QueryTokenIden::Name,
+            QueryTokenIden::Hash,
+            QueryTokenIden::CreatedAt,
+        ])
+        .from(QueryTokenIden::Table)
+        .and_where(Expr::col(QueryTokenIden::UserId).eq(user_id))
+        .order_by(QueryTokenIden::CreatedAt, Order::Desc)
+        .order_by(QueryTokenIden::Id, Order::Desc)
+        .to_owned();
+
+    pool.fetch_all::<QueryToken, _>(&statement).await
+}
+
+/// Id of the user the secret was issued to, `None` when unknown or revoked.
+pub async fn find_token_user_id(pool: &QueryPool, secret: &str) -> anyhow::Result<Option<String>> {
+    let statement = Query::select()
+        .column(QueryTokenIden::UserId)
+        .from(QueryTokenIden::Table)
+        .and_where(Expr::col(QueryTokenIden::Hash).eq(hash_secret(secret)))
+        .to_owned();
+
+    let token = pool.fetch_optional::<(String,), _>(&statement).await?;
+
+    Ok(token.map(|(user_id,)| user_id))
+}
+
+pub async fn reset_query_tokens(pool: &QueryPool) -> anyhow::Result<()> {
+    let statement = Query::delete().from_table(QueryTokenIden::Table).to_owned();
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub fn subscribe_query_tokens<E: evento::Executor + Clone>(
+    region: impl Into<String>,
+) -> anyhow::Result<SubscribeBuilder<E>> {
+    let region = region.into();
+
+    Ok(
+        evento::subscribe(format!("identity.{region}.token.query.tokens"))
+            .routing_key(region)
+            .aggregator::<Token>()
+            .handler(Reported(tokens_created()))
+            .handler(Reported(tokens_revoked())),
+    )
+}
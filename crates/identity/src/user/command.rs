@@ -0,0 +1,157 @@
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use serde::Deserialize;
+use timada_shared::{Conflict, QueryPool};
+use validator::Validate;
+
+use crate::user::{Registered, Role, RolesChanged, User, find_user_id_by_email};
+
+#[derive(Validate, Deserialize)]
+pub struct RegisterInput {
+    #[validate(email, length(max = 255))]
+    pub email: String,
+    #[validate(length(min = 8, max = 128))]
+    pub password: String,
+    /// Shop or organisation the user acts for, products are scoped to it.
+    #[validate(length(min = 1, max = 26))]
+    pub owner_id: Option<String>,
+    #[validate(length(min = 1))]
+    pub roles: Vec<Role>,
+}
+
+pub async fn register(
+    pool: &QueryPool,
+    input: RegisterInput,
+) -> anyhow::Result<evento::SaveBuilder<User>> {
+    input.validate()?;
+
+    let email = input.email.trim().to_lowercase();
+    if find_user_id_by_email(pool, &email).await?.is_some() {
+        anyhow::bail!(Conflict::new("email already registered"));
+    }
+
+    let salt = SaltString::generate(&mut OsRng);
+    let password_hash = Argon2::default()
+        .hash_password(input.password.as_bytes(), &salt)
+        .map_err(|err| anyhow::anyhow!("{err}"))?
+        .to_string();
+
+    Ok(evento::create().data(&Registered {
+        email,
+        password_hash,
+        owner_id: input.owner_id,
+        roles: input.roles,
+    })?)
+}
+
+#[derive(Validate, Deserialize)]
+pub struct RegisterExternalInput {
+    #[validate(email, length(max = 255))]
+    pub email: String,
+    #[validate(length(min = 1, max = 26))]
+    pub owner_id: Option<String>,
+    #[validate(length(min = 1))]
+    pub roles: Vec<Role>,
+}
+
+/// Registers a user authenticated by an identity provider, it has no password and can not log in
+/// with the login form.
+pub async fn register_external(
+    pool: &QueryPool,
+    input: RegisterExternalInput,
+) -> anyhow::Result<evento::SaveBuilder<User>> {
+    input.validate()?;
+
+    let email = input.email.trim().to_lowercase();
+    if find_user_id_by_email(pool, &email).await?.is_some() {
+        anyhow::bail!(Conflict::new("email already registered"));
+    }
+
+    Ok(evento::create().data(&Registered {
+        email,
+        password_hash: "".to_owned(),
+        owner_id: input.owner_id,
+        roles: input.roles,
+    })?)
+}
+
+#[derive(Validate, Deserialize)]
+pub struct ChangeRolesInput {
+    #[validate(length(min = 1))]
+    pub roles: Vec<Role>,
+}
+
+pub async fn change_roles<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: ChangeRolesInput,
+) -> anyhow::Result<evento::SaveBuilder<User>> {
+    input.validate()?;
+
+    let user = evento::load::<User, _>(executor, id).await?;
+
+    Ok(evento::save_with(user).data(&RolesChanged { roles: input.roles })?)
+}
+
+#[derive(Deserialize)]
+pub struct LoginInput {
+    pub email: String,
+    pub password: String,
+}
+
+/// Returns the id of the user the credentials belong to.
+pub async fn login<E: evento::Executor>(
+    executor: &E,
+    pool: &QueryPool,
+    input: LoginInput,
+) -> anyhow::Result<String> {
+    let email = input.email.trim().to_lowercase();
+    let Some(id) = find_user_id_by_email(pool, &email).await? else {
+        anyhow::bail!("invalid email or password");
+    };
+
+    let user = evento::load::<User, _>(executor, &id).await?;
+    if user.item.password_hash.is_empty() {
+        anyhow::bail!("invalid email or password");
+    }
+
+    let password_hash =
+        PasswordHash::new(&user.item.password_hash).map_err(|err| anyhow::anyhow!("{err}"))?;
+
+    if Argon2::default()
+        .verify_password(input.password.as_bytes(), &password_hash)
+        .is_err()
+    {
+        anyhow::bail!("invalid email or password");
+    }
+
+    Ok(id)
+}
+
+/// Returns the id of the user registered by an identity provider under this email, `None` when
+/// none is. Password accounts are never linked, the identity provider does not prove who owns
+/// them, and the owner of the claims must be the owner of the user.
+pub async fn login_external<E: evento::Executor>(
+    executor: &E,
+    pool: &QueryPool,
+    email: &str,
+    owner_id: Option<&str>,
+) -> anyhow::Result<Option<String>> {
+    let email = email.trim().to_lowercase();
+    let Some(id) = find_user_id_by_email(pool, &email).await? else {
+        return Ok(None);
+    };
+
+    let user = evento::load::<User, _>(executor, &id).await?;
+    if !user.item.password_hash.is_empty() {
+        anyhow::bail!("email already registered with a password");
+    }
+
+    if user.item.owner_id.as_deref() != owner_id {
+        anyhow::bail!("account belongs to another owner");
+    }
+
+    Ok(Some(id))
+}
@@ -0,0 +1,54 @@
+mod command;
+mod query;
+mod role;
+
+use evento::AggregatorName;
+use serde::{Deserialize, Serialize};
+
+pub use command::*;
+pub use query::*;
+pub use role::*;
+
+use crate::RequestEvent;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct User {
+    pub email: String,
+    pub password_hash: String,
+    pub owner_id: Option<String>,
+    pub roles: Vec<Role>,
+}
+
+#[evento::aggregator]
+impl User {
+    async fn registered(&mut self, event: RequestEvent<Registered>) -> anyhow::Result<()> {
+        self.email = event.data.email;
+        self.password_hash = event.data.password_hash;
+        self.owner_id = event.data.owner_id;
+        self.roles = event.data.roles;
+
+        Ok(())
+    }
+
+    async fn roles_changed(&mut self, event: RequestEvent<RolesChanged>) -> anyhow::Result<()> {
+        self.roles = event.data.roles;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Registered {
+    pub email: String,
+    /// Empty for users of an identity provider.
+    pub password_hash: String,
+    #[serde(default)]
+    pub owner_id: Option<String>,
+    #[serde(default = "role::default_roles")]
+    pub roles: Vec<Role>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct RolesChanged {
+    pub roles: Vec<Role>,
+}
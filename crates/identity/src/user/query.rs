@@ -0,0 +1,135 @@
+use crate::{
+    RequestEvent,
+    user::{Permission, Registered, Role, RolesChanged, User, role::join_roles},
+};
+use evento::{AggregatorName, SubscribeBuilder};
+use sea_query::{Expr, ExprTrait, OnConflict, Query};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use timada_shared::{QueryPool, Reported, format_created_at};
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryUser {
+    pub id: String,
+    pub email: String,
+    pub owner_id: Option<String>,
+    pub roles: String,
+    pub created_at: String,
+}
+
+impl QueryUser {
+    pub fn roles(&self) -> Vec<Role> {
+        self.roles
+            .split(',')
+            .filter_map(|role| role.parse().ok())
+            .collect()
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.roles().iter().any(|role| role.grants(permission))
+    }
+}
+
+#[evento::handler(User)]
+async fn users_registered<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Registered>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::insert()
+        .into_table(QueryUserIden::Table)
+        .columns([
+            QueryUserIden::Id,
+            QueryUserIden::Email,
+            QueryUserIden::OwnerId,
+            QueryUserIden::Roles,
+            QueryUserIden::CreatedAt,
+        ])
+        .values_panic([
+            event.aggregator_id.to_owned().into(),
+            event.data.email.to_owned().into(),
+            event.data.owner_id.to_owned().into(),
+            join_roles(&event.data.roles).into(),
+            format_created_at(event.timestamp).into(),
+        ])
+        .on_conflict(
+            OnConflict::new()
+                .do_nothing_on([QueryUserIden::Email])
+                .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(User)]
+async fn users_roles_changed<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<RolesChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryUserIden::Table)
+        .values([(QueryUserIden::Roles, join_roles(&event.data.roles).into())])
+        .and_where(Expr::col(QueryUserIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub async fn find_user(pool: &QueryPool, id: &str) -> anyhow::Result<Option<QueryUser>> {
+    let statement = Query::select()
+        .columns([
+            QueryUserIden::Id,
+            QueryUserIden::Email,
+            QueryUserIden::OwnerId,
+            QueryUserIden::Roles,
+            QueryUserIden::CreatedAt,
+        ])
+        .from(QueryUserIden::Table)
+        .and_where(Expr::col(QueryUserIden::Id).eq(id))
+        .to_owned();
+
+    pool.fetch_optional::<QueryUser, _>(&statement).await
+}
+
+pub async fn find_user_id_by_email(
+    pool: &QueryPool,
+    email: &str,
+) -> anyhow::Result<Option<String>> {
+    let statement = Query::select()
+        .column(QueryUserIden::Id)
+        .from(QueryUserIden::Table)
+        .and_where(Expr::col(QueryUserIden::Email).eq(email))
+        .to_owned();
+
+    let user = pool.fetch_optional::<(String,), _>(&statement).await?;
+
+    Ok(user.map(|(id,)| id))
+}
+
+pub async fn reset_query_users(pool: &QueryPool) -> anyhow::Result<()> {
+    let statement = Query::delete().from_table(QueryUserIden::Table).to_owned();
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub fn subscribe_query_users<E: evento::Executor + Clone>(
+    region: impl Into<String>,
+) -> anyhow::Result<SubscribeBuilder<E>> {
+    let region = region.into();
+
+    Ok(
+        evento::subscribe(format!("identity.{region}.user.query.users"))
+            .routing_key(region)
+            .aggregator::<User>()
+            .handler(Reported(users_registered()))
+            .handler(Reported(users_roles_changed())),
+    )
+}
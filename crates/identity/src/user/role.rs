@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumString, EnumIter)]
+pub enum Permission {
+    #[strum(serialize = "market.product.read")]
+    MarketProductRead,
+    #[strum(serialize = "market.product.create")]
+    MarketProductCreate,
+    #[strum(serialize = "market.product.update")]
+    MarketProductUpdate,
+    #[strum(serialize = "market.product.archive")]
+    MarketProductArchive,
+    #[strum(serialize = "market.product.delete")]
+    MarketProductDelete,
+    #[strum(serialize = "market.category.read")]
+    MarketCategoryRead,
+    #[strum(serialize = "market.category.manage")]
+    MarketCategoryManage,
+    #[strum(serialize = "inventory.stock.update")]
+    InventoryStockUpdate,
+    #[strum(serialize = "admin.projection.read")]
+    AdminProjectionRead,
+    #[strum(serialize = "identity.token.manage")]
+    IdentityTokenManage,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Display, EnumString)]
+#[strum(serialize_all = "kebab_case")]
+pub enum Role {
+    Admin,
+    /// Sees products and categories, changes nothing.
+    Support,
+}
+
+impl Role {
+    pub fn permissions(&self) -> Vec<Permission> {
+        match self {
+            Self::Admin => Permission::iter().collect(),
+            Self::Support => vec![
+                Permission::MarketProductRead,
+                Permission::MarketCategoryRead,
+            ],
+        }
+    }
+
+    pub fn grants(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+/// Users registered before roles existed were all admins.
+pub(crate) fn default_roles() -> Vec<Role> {
+    vec![Role::Admin]
+}
+
+/// Stored as a comma separated list in the query store.
+pub(crate) fn join_roles(roles: &[Role]) -> String {
+    roles
+        .iter()
+        .map(Role::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
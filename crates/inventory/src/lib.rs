@@ -0,0 +1,7 @@
+use evento::EventDetails;
+use timada_shared::RequestMetadata;
+
+pub mod migrator;
+pub mod stock;
+
+pub(crate) type RequestEvent<D> = EventDetails<D, RequestMetadata>;
@@ -0,0 +1,99 @@
+use crate::stock::QueryStockIden;
+use sea_query::{ColumnDef, Expr, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+pub struct CreateStockTableOperation;
+
+impl Statements for CreateStockTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(QueryStockIden::Table)
+                .col(
+                    ColumnDef::new(QueryStockIden::Id)
+                        .string()
+                        .string_len(105)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryStockIden::ProductId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryStockIden::VariantId)
+                        .string()
+                        .string_len(26),
+                )
+                .col(
+                    ColumnDef::new(QueryStockIden::Location)
+                        .string()
+                        .string_len(50)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryStockIden::OnHand)
+                        .big_integer()
+                        .not_null()
+                        .default(0),
+                )
+                .col(
+                    ColumnDef::new(QueryStockIden::Available)
+                        .big_integer()
+                        .not_null()
+                        .default(0),
+                )
+                .col(
+                    ColumnDef::new(QueryStockIden::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(QueryStockIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateStockProductIdxOperation;
+
+impl Statements for CreateStockProductIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_stock_product_id")
+                .table(QueryStockIden::Table)
+                .col(QueryStockIden::ProductId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_stock_product_id")
+                .table(QueryStockIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Inventory202610171810;
+
+timada_shared::query_migration!(
+    Inventory202610171810,
+    "main",
+    "inventory_2026_10_17_18_10",
+    vec_box![],
+    vec_box![CreateStockTableOperation, CreateStockProductIdxOperation]
+);
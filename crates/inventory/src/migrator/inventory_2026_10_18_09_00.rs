@@ -0,0 +1,14 @@
+use sqlx_migrator::vec_box;
+use timada_shared::CreatedAtToStringOperation;
+
+use super::inventory_2026_10_17_18_10::Inventory202610171810;
+
+pub struct Inventory202610180900;
+
+timada_shared::query_migration!(
+    Inventory202610180900,
+    "main",
+    "inventory_2026_10_18_09_00",
+    vec_box![Inventory202610171810],
+    vec_box![CreatedAtToStringOperation("query_stock")]
+);
@@ -0,0 +1,13 @@
+mod inventory_2026_10_17_18_10;
+mod inventory_2026_10_18_09_00;
+
+use inventory_2026_10_17_18_10::Inventory202610171810;
+use inventory_2026_10_18_09_00::Inventory202610180900;
+use timada_shared::QueryMigrator;
+
+pub fn add_migrations(migrator: &mut QueryMigrator) -> Result<(), sqlx_migrator::Error> {
+    migrator.add_migration(Inventory202610171810)?;
+    migrator.add_migration(Inventory202610180900)?;
+
+    Ok(())
+}
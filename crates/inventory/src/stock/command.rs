@@ -0,0 +1,196 @@
+use evento::{AggregatorName, SubscribeBuilder};
+use serde::Deserialize;
+use timada_market::product::{
+    Archived, CategoryAssigned, CategoryUnassigned, CreateFailed, CreateRequested,
+    Created as ProductCreated, Deleted, PriceChanged, PriceScheduled, PriceUnscheduled, Product,
+    ProductState, Restored, Updated, VariantAdded, VariantRemoved,
+};
+use timada_shared::{Conflict, NotFound, Reported, invalid_field};
+use validator::Validate;
+
+use crate::{
+    RequestEvent,
+    stock::{Created, DEFAULT_LOCATION, Stock, StockAdjusted, StockReceived, StockReserved},
+};
+
+#[derive(Validate, Deserialize)]
+pub struct ReceiveInput {
+    pub variant_id: Option<String>,
+    #[validate(length(max = 50))]
+    pub location: Option<String>,
+    #[validate(range(min = 1))]
+    pub quantity: i64,
+}
+
+pub async fn receive<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: ReceiveInput,
+) -> anyhow::Result<evento::SaveBuilder<Stock>> {
+    input.validate()?;
+
+    let stock = evento::load::<Stock, _>(executor, id).await?;
+    let variant_id =
+        load_variant_id(executor, &stock.event.aggregator_id, input.variant_id).await?;
+
+    Ok(evento::save_with(stock).data(&StockReceived {
+        variant_id,
+        location: location_or_default(input.location),
+        quantity: input.quantity,
+    })?)
+}
+
+#[derive(Validate, Deserialize)]
+pub struct AdjustInput {
+    pub variant_id: Option<String>,
+    #[validate(length(max = 50))]
+    pub location: Option<String>,
+    pub quantity: i64,
+    #[validate(length(min = 3, max = 100))]
+    pub reason: String,
+}
+
+pub async fn adjust<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: AdjustInput,
+) -> anyhow::Result<evento::SaveBuilder<Stock>> {
+    input.validate()?;
+
+    if input.quantity == 0 {
+        anyhow::bail!(invalid_field("quantity", "quantity cannot be zero"));
+    }
+
+    let stock = evento::load::<Stock, _>(executor, id).await?;
+    let variant_id =
+        load_variant_id(executor, &stock.event.aggregator_id, input.variant_id).await?;
+    let location = location_or_default(input.location);
+    let level = stock.item.level(&variant_id, &location).cloned();
+    let level = level.unwrap_or_default();
+
+    if level.on_hand + input.quantity < level.reserved {
+        anyhow::bail!(Conflict::new("not enough stock on hand"));
+    }
+
+    Ok(evento::save_with(stock).data(&StockAdjusted {
+        variant_id,
+        location,
+        quantity: input.quantity,
+        reason: input.reason,
+    })?)
+}
+
+#[derive(Validate, Deserialize)]
+pub struct ReserveInput {
+    pub variant_id: Option<String>,
+    #[validate(length(max = 50))]
+    pub location: Option<String>,
+    #[validate(range(min = 1))]
+    pub quantity: i64,
+}
+
+pub async fn reserve<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: ReserveInput,
+) -> anyhow::Result<evento::SaveBuilder<Stock>> {
+    input.validate()?;
+
+    let stock = evento::load::<Stock, _>(executor, id).await?;
+    let variant_id =
+        load_variant_id(executor, &stock.event.aggregator_id, input.variant_id).await?;
+    let location = location_or_default(input.location);
+    let available = stock
+        .item
+        .level(&variant_id, &location)
+        .map(|level| level.available())
+        .unwrap_or_default();
+
+    if available < input.quantity {
+        anyhow::bail!(Conflict::new("not enough stock available"));
+    }
+
+    Ok(evento::save_with(stock).data(&StockReserved {
+        variant_id,
+        location,
+        quantity: input.quantity,
+    })?)
+}
+
+fn location_or_default(location: Option<String>) -> String {
+    location
+        .map(|location| location.trim().to_lowercase())
+        .filter(|location| !location.is_empty())
+        .unwrap_or(DEFAULT_LOCATION.to_owned())
+}
+
+async fn load_variant_id<E: evento::Executor>(
+    executor: &E,
+    product_id: &str,
+    variant_id: Option<String>,
+) -> anyhow::Result<Option<String>> {
+    let product = evento::load::<Product, _>(executor, product_id).await?;
+    if product.item.state == ProductState::Deleted {
+        anyhow::bail!(Conflict::new("product deleted"));
+    }
+
+    let variant_id = variant_id.filter(|id| !id.is_empty());
+    if let Some(variant_id) = &variant_id
+        && !product
+            .item
+            .variants
+            .iter()
+            .any(|variant| &variant.id == variant_id)
+    {
+        anyhow::bail!(NotFound::new("variant not found"));
+    }
+
+    Ok(variant_id)
+}
+
+#[evento::handler(Product)]
+async fn command_product_created<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<ProductCreated>,
+) -> anyhow::Result<()> {
+    match evento::load::<Stock, _>(context.executor, &event.aggregator_id).await {
+        Err(evento::ReadError::NotFound) => {}
+        Ok(_) => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+
+    evento::SaveBuilder::new(Some(Stock::default()), &event.aggregator_id)
+        .routing_key_opt(event.routing_key.to_owned())
+        .data(&Created {
+            location: DEFAULT_LOCATION.to_owned(),
+        })?
+        .metadata(&event.metadata)?
+        .commit(context.executor)
+        .await?;
+
+    Ok(())
+}
+
+pub fn subscribe_command<E: evento::Executor + Clone>(
+    region: impl Into<String>,
+) -> SubscribeBuilder<E> {
+    let region = region.into();
+
+    evento::subscribe(format!("inventory.{region}.stock.command"))
+        .routing_key(region)
+        .aggregator::<Product>()
+        .skip::<Product, CreateRequested>()
+        .skip::<Product, CreateFailed>()
+        .skip::<Product, Updated>()
+        .skip::<Product, Archived>()
+        .skip::<Product, Restored>()
+        .skip::<Product, Deleted>()
+        .skip::<Product, CategoryAssigned>()
+        .skip::<Product, CategoryUnassigned>()
+        .skip::<Product, VariantAdded>()
+        .skip::<Product, VariantRemoved>()
+        .skip::<Product, PriceChanged>()
+        .skip::<Product, PriceScheduled>()
+        .skip::<Product, PriceUnscheduled>()
+        .handler(Reported(command_product_created()))
+}
@@ -0,0 +1,118 @@
+mod command;
+mod query;
+
+use evento::AggregatorName;
+use serde::{Deserialize, Serialize};
+
+pub use command::*;
+pub use query::*;
+
+use crate::RequestEvent;
+
+pub const DEFAULT_LOCATION: &str = "default";
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StockLevel {
+    pub variant_id: Option<String>,
+    pub location: String,
+    pub on_hand: i64,
+    pub reserved: i64,
+}
+
+impl StockLevel {
+    pub fn available(&self) -> i64 {
+        self.on_hand - self.reserved
+    }
+}
+
+/// Stock levels of a product, the aggregate id is the product id.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Stock {
+    pub levels: Vec<StockLevel>,
+}
+
+impl Stock {
+    pub fn level(&self, variant_id: &Option<String>, location: &str) -> Option<&StockLevel> {
+        self.levels
+            .iter()
+            .find(|level| &level.variant_id == variant_id && level.location == location)
+    }
+
+    fn level_mut(&mut self, variant_id: &Option<String>, location: &str) -> &mut StockLevel {
+        let index = self
+            .levels
+            .iter()
+            .position(|level| &level.variant_id == variant_id && level.location == location);
+
+        let index = match index {
+            Some(index) => index,
+            _ => {
+                self.levels.push(StockLevel {
+                    variant_id: variant_id.to_owned(),
+                    location: location.to_owned(),
+                    ..Default::default()
+                });
+                self.levels.len() - 1
+            }
+        };
+
+        &mut self.levels[index]
+    }
+}
+
+#[evento::aggregator]
+impl Stock {
+    async fn created(&mut self, event: RequestEvent<Created>) -> anyhow::Result<()> {
+        self.level_mut(&None, &event.data.location);
+
+        Ok(())
+    }
+
+    async fn stock_received(&mut self, event: RequestEvent<StockReceived>) -> anyhow::Result<()> {
+        let level = self.level_mut(&event.data.variant_id, &event.data.location);
+        level.on_hand += event.data.quantity;
+
+        Ok(())
+    }
+
+    async fn stock_adjusted(&mut self, event: RequestEvent<StockAdjusted>) -> anyhow::Result<()> {
+        let level = self.level_mut(&event.data.variant_id, &event.data.location);
+        level.on_hand += event.data.quantity;
+
+        Ok(())
+    }
+
+    async fn stock_reserved(&mut self, event: RequestEvent<StockReserved>) -> anyhow::Result<()> {
+        let level = self.level_mut(&event.data.variant_id, &event.data.location);
+        level.reserved += event.data.quantity;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Created {
+    pub location: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct StockReceived {
+    pub variant_id: Option<String>,
+    pub location: String,
+    pub quantity: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct StockAdjusted {
+    pub variant_id: Option<String>,
+    pub location: String,
+    pub quantity: i64,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct StockReserved {
+    pub variant_id: Option<String>,
+    pub location: String,
+    pub quantity: i64,
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+
+use crate::{
+    RequestEvent,
+    stock::{Created, Stock, StockAdjusted, StockReceived, StockReserved},
+};
+use evento::{AggregatorName, SubscribeBuilder, sql::Reader};
+use sea_query::{Expr, ExprTrait, OnConflict, Query};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use timada_shared::{QueryPool, Reported, format_created_at};
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryStock {
+    pub id: String,
+    pub product_id: String,
+    pub variant_id: Option<String>,
+    pub location: String,
+    pub on_hand: i64,
+    pub available: i64,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryStockCursor {
+    pub i: String,
+    pub c: String,
+}
+
+impl evento::cursor::Cursor for QueryStock {
+    type T = QueryStockCursor;
+
+    fn serialize(&self) -> Self::T {
+        Self::T {
+            i: self.id.to_owned(),
+            c: self.created_at.to_owned(),
+        }
+    }
+}
+
+impl evento::sql::Bind for QueryStock {
+    type T = QueryStockIden;
+    type I = [Self::T; 2];
+    type V = [Expr; 2];
+    type Cursor = Self;
+
+    fn columns() -> Self::I {
+        [QueryStockIden::CreatedAt, QueryStockIden::Id]
+    }
+
+    fn values(
+        cursor: <<Self as evento::sql::Bind>::Cursor as evento::cursor::Cursor>::T,
+    ) -> Self::V {
+        [cursor.c.into(), cursor.i.into()]
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+pub struct QueryStockTotal {
+    pub product_id: String,
+    pub on_hand: i64,
+    pub available: i64,
+}
+
+/// Writes the absolute level of the aggregate, replaying an event leaves the row unchanged.
+async fn upsert_stock<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    product_id: &str,
+    variant_id: &Option<String>,
+    location: &str,
+    created_at: i64,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let stock = evento::load::<Stock, _>(context.executor, product_id).await?;
+    let (on_hand, available) = stock
+        .item
+        .level(variant_id, location)
+        .map(|level| (level.on_hand, level.available()))
+        .unwrap_or_default();
+
+    let id = format!(
+        "{product_id}:{}:{location}",
+        variant_id.to_owned().unwrap_or_default()
+    );
+
+    let statement = Query::insert()
+        .into_table(QueryStockIden::Table)
+        .columns([
+            QueryStockIden::Id,
+            QueryStockIden::ProductId,
+            QueryStockIden::VariantId,
+            QueryStockIden::Location,
+            QueryStockIden::OnHand,
+            QueryStockIden::Available,
+            QueryStockIden::CreatedAt,
+        ])
+        .values_panic([
+            id.into(),
+            product_id.to_owned().into(),
+            variant_id.to_owned().into(),
+            location.to_owned().into(),
+            on_hand.into(),
+            available.into(),
+            format_created_at(created_at).into(),
+        ])
+        .on_conflict(
+            OnConflict::column(QueryStockIden::Id)
+                .update_columns([QueryStockIden::OnHand, QueryStockIden::Available])
+                .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Stock)]
+async fn stocks_created<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Created>,
+) -> anyhow::Result<()> {
+    upsert_stock(
+        context,
+        &event.aggregator_id,
+        &None,
+        &event.data.location,
+        event.timestamp,
+    )
+    .await
+}
+
+#[evento::handler(Stock)]
+async fn stocks_stock_received<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<StockReceived>,
+) -> anyhow::Result<()> {
+    let data = &event.data;
+    upsert_stock(
+        context,
+        &event.aggregator_id,
+        &data.variant_id,
+        &data.location,
+        event.timestamp,
+    )
+    .await
+}
+
+#[evento::handler(Stock)]
+async fn stocks_stock_adjusted<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<StockAdjusted>,
+) -> anyhow::Result<()> {
+    let data = &event.data;
+    upsert_stock(
+        context,
+        &event.aggregator_id,
+        &data.variant_id,
+        &data.location,
+        event.timestamp,
+    )
+    .await
+}
+
+#[evento::handler(Stock)]
+async fn stocks_stock_reserved<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<StockReserved>,
+) -> anyhow::Result<()> {
+    let data = &event.data;
+    upsert_stock(
+        context,
+        &event.aggregator_id,
+        &data.variant_id,
+        &data.location,
+        event.timestamp,
+    )
+    .await
+}
+
+pub async fn query_stocks(
+    pool: &QueryPool,
+    product_id: impl Into<String>,
+) -> anyhow::Result<evento::cursor::ReadResult<QueryStock>> {
+    let statement = Query::select()
+        .columns([
+            QueryStockIden::Id,
+            QueryStockIden::ProductId,
+            QueryStockIden::VariantId,
+            QueryStockIden::Location,
+            QueryStockIden::OnHand,
+            QueryStockIden::Available,
+            QueryStockIden::CreatedAt,
+        ])
+        .from(QueryStockIden::Table)
+        .and_where(Expr::col(QueryStockIden::ProductId).eq(product_id.into()))
+        .to_owned();
+
+    pool.read(Reader::new(statement).forward(1000, None)).await
+}
+
+pub async fn query_stock_totals(
+    pool: &QueryPool,
+    product_ids: Vec<String>,
+) -> anyhow::Result<HashMap<String, QueryStockTotal>> {
+    let statement = Query::select()
+        .columns([
+            QueryStockIden::ProductId,
+            QueryStockIden::OnHand,
+            QueryStockIden::Available,
+        ])
+        .from(QueryStockIden::Table)
+        .and_where(Expr::col(QueryStockIden::ProductId).is_in(product_ids))
+        .to_owned();
+
+    // Summed here, `SUM` of a bigint is a decimal on MySQL and Postgres.
+    let mut totals = HashMap::<String, QueryStockTotal>::new();
+    for stock in pool.fetch_all::<QueryStockTotal, _>(&statement).await? {
+        let total = totals
+            .entry(stock.product_id.to_owned())
+            .or_insert_with(|| QueryStockTotal {
+                product_id: stock.product_id.to_owned(),
+                on_hand: 0,
+                available: 0,
+            });
+
+        total.on_hand += stock.on_hand;
+        total.available += stock.available;
+    }
+
+    Ok(totals)
+}
+
+pub async fn reset_query_stocks(pool: &QueryPool) -> anyhow::Result<()> {
+    let statement = Query::delete().from_table(QueryStockIden::Table).to_owned();
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub fn subscribe_query_stocks<E: evento::Executor + Clone>(
+    region: impl Into<String>,
+) -> anyhow::Result<SubscribeBuilder<E>> {
+    let region = region.into();
+
+    Ok(
+        evento::subscribe(format!("inventory.{region}.stock.query.stocks"))
+            .routing_key(region)
+            .aggregator::<Stock>()
+            .handler(Reported(stocks_created()))
+            .handler(Reported(stocks_stock_received()))
+            .handler(Reported(stocks_stock_adjusted()))
+            .handler(Reported(stocks_stock_reserved())),
+    )
+}
@@ -0,0 +1,328 @@
+use evento::{AggregatorName, SubscribeBuilder};
+use sea_query::{Expr, ExprTrait, OnConflict, Query};
+use serde::Deserialize;
+use timada_shared::{Conflict, QueryPool, Reported, invalid_field};
+use validator::Validate;
+
+use crate::{
+    RequestEvent,
+    category::{Category, Created, Deleted, Moved, Renamed},
+    product::{
+        Archived, CategoryAssigned, CategoryUnassigned, CreateFailed, CreateRequested,
+        Created as ProductCreated, Deleted as ProductDeleted, PriceChanged, PriceScheduled,
+        PriceUnscheduled, Product, Restored, Updated, VariantAdded, VariantRemoved,
+    },
+};
+
+/// Parent of each category, the children moved up when their parent is deleted.
+#[sea_query::enum_def]
+pub struct CommandCategory {
+    pub id: String,
+    pub parent_id: Option<String>,
+}
+
+/// Products of each category, unassigned when the category is deleted.
+#[sea_query::enum_def]
+pub struct CommandCategoryProduct {
+    pub category_id: String,
+    pub product_id: String,
+}
+
+#[derive(Validate, Deserialize)]
+pub struct CreateInput {
+    #[validate(length(min = 3, max = 25))]
+    pub name: String,
+    pub parent_id: Option<String>,
+}
+
+pub async fn create<E: evento::Executor>(
+    executor: &E,
+    input: CreateInput,
+) -> anyhow::Result<evento::SaveBuilder<Category>> {
+    input.validate()?;
+
+    let parent_id = input.parent_id.filter(|id| !id.is_empty());
+    if let Some(parent_id) = &parent_id {
+        load_parent(executor, parent_id).await?;
+    }
+
+    Ok(evento::create().data(&Created {
+        name: input.name,
+        parent_id,
+    })?)
+}
+
+#[derive(Validate, Deserialize)]
+pub struct RenameInput {
+    #[validate(length(min = 3, max = 25))]
+    pub name: String,
+}
+
+pub async fn rename<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: RenameInput,
+) -> anyhow::Result<evento::SaveBuilder<Category>> {
+    input.validate()?;
+
+    let category = evento::load::<Category, _>(executor, id).await?;
+    if category.item.deleted {
+        anyhow::bail!(Conflict::new("category deleted"));
+    }
+
+    Ok(evento::save_with(category).data(&Renamed { name: input.name })?)
+}
+
+#[derive(Deserialize)]
+pub struct MoveInput {
+    pub parent_id: Option<String>,
+}
+
+pub async fn move_to<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: MoveInput,
+) -> anyhow::Result<evento::SaveBuilder<Category>> {
+    let category = evento::load::<Category, _>(executor, id).await?;
+    if category.item.deleted {
+        anyhow::bail!(Conflict::new("category deleted"));
+    }
+
+    let parent_id = input.parent_id.filter(|id| !id.is_empty());
+    let mut ancestor_id = parent_id.to_owned();
+    let mut depth = 0;
+
+    while let Some(id) = ancestor_id {
+        if id == category.event.aggregator_id {
+            anyhow::bail!(invalid_field(
+                "parent_id",
+                "category cannot be moved into itself or one of its children"
+            ));
+        }
+
+        depth += 1;
+        if depth > 32 {
+            anyhow::bail!(invalid_field("parent_id", "category tree too deep"));
+        }
+
+        ancestor_id = load_parent(executor, &id).await?.parent_id;
+    }
+
+    Ok(evento::save_with(category).data(&Moved { parent_id })?)
+}
+
+pub async fn delete<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+) -> anyhow::Result<evento::SaveBuilder<Category>> {
+    let category = evento::load::<Category, _>(executor, id).await?;
+    if category.item.deleted {
+        anyhow::bail!(Conflict::new("category deleted"));
+    }
+
+    Ok(evento::save_with(category).data(&Deleted {})?)
+}
+
+async fn load_parent<E: evento::Executor>(executor: &E, id: &str) -> anyhow::Result<Category> {
+    let parent = evento::load::<Category, _>(executor, id).await?;
+    if parent.item.deleted {
+        anyhow::bail!(Conflict::new("parent category deleted"));
+    }
+
+    Ok(parent.item)
+}
+
+#[evento::handler(Category)]
+async fn command_created<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Created>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::insert()
+        .into_table(CommandCategoryIden::Table)
+        .columns([CommandCategoryIden::Id, CommandCategoryIden::ParentId])
+        .values_panic([
+            event.aggregator_id.to_owned().into(),
+            event.data.parent_id.to_owned().into(),
+        ])
+        .on_conflict(
+            OnConflict::column(CommandCategoryIden::Id)
+                .do_nothing_on([CommandCategoryIden::Id])
+                .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Category)]
+async fn command_moved<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Moved>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(CommandCategoryIden::Table)
+        .value(
+            CommandCategoryIden::ParentId,
+            event.data.parent_id.to_owned(),
+        )
+        .and_where(Expr::col(CommandCategoryIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Category)]
+async fn command_deleted<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Deleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let category = evento::load::<Category, _>(context.executor, &event.aggregator_id).await?;
+
+    let statement = Query::select()
+        .column(CommandCategoryIden::Id)
+        .from(CommandCategoryIden::Table)
+        .and_where(Expr::col(CommandCategoryIden::ParentId).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    let children = pool.fetch_all::<(String,), _>(&statement).await?;
+
+    for (id,) in children {
+        let child = evento::load::<Category, _>(context.executor, id).await?;
+        if child.item.parent_id.as_ref() != Some(&event.aggregator_id) {
+            continue;
+        }
+
+        evento::save_with(child)
+            .data(&Moved {
+                parent_id: category.item.parent_id.to_owned(),
+            })?
+            .metadata(&event.metadata)?
+            .commit(context.executor)
+            .await?;
+    }
+
+    let statement = Query::select()
+        .column(CommandCategoryProductIden::ProductId)
+        .from(CommandCategoryProductIden::Table)
+        .and_where(
+            Expr::col(CommandCategoryProductIden::CategoryId).eq(event.aggregator_id.to_owned()),
+        )
+        .to_owned();
+
+    let products = pool.fetch_all::<(String,), _>(&statement).await?;
+
+    for (id,) in products {
+        let product = evento::load::<Product, _>(context.executor, id).await?;
+        if !product.item.category_ids.contains(&event.aggregator_id) {
+            continue;
+        }
+
+        evento::save_with(product)
+            .data(&CategoryUnassigned {
+                category_id: event.aggregator_id.to_owned(),
+            })?
+            .metadata(&event.metadata)?
+            .commit(context.executor)
+            .await?;
+    }
+
+    let statement = Query::delete()
+        .from_table(CommandCategoryIden::Table)
+        .and_where(Expr::col(CommandCategoryIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn command_category_assigned<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<CategoryAssigned>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::insert()
+        .into_table(CommandCategoryProductIden::Table)
+        .columns([
+            CommandCategoryProductIden::CategoryId,
+            CommandCategoryProductIden::ProductId,
+        ])
+        .values_panic([
+            event.data.category_id.to_owned().into(),
+            event.aggregator_id.to_owned().into(),
+        ])
+        .on_conflict(
+            OnConflict::columns([
+                CommandCategoryProductIden::CategoryId,
+                CommandCategoryProductIden::ProductId,
+            ])
+            .do_nothing_on([
+                CommandCategoryProductIden::CategoryId,
+                CommandCategoryProductIden::ProductId,
+            ])
+            .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn command_category_unassigned<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<CategoryUnassigned>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(CommandCategoryProductIden::Table)
+        .and_where(
+            Expr::col(CommandCategoryProductIden::CategoryId).eq(event.data.category_id.to_owned()),
+        )
+        .and_where(
+            Expr::col(CommandCategoryProductIden::ProductId).eq(event.aggregator_id.to_owned()),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub fn subscribe_command<E: evento::Executor + Clone>(
+    region: impl Into<String>,
+) -> SubscribeBuilder<E> {
+    let region = region.into();
+
+    evento::subscribe(format!("market.{region}.category.command"))
+        .routing_key(region)
+        .aggregator::<Category>()
+        .aggregator::<Product>()
+        .skip::<Category, Renamed>()
+        .skip::<Product, CreateRequested>()
+        .skip::<Product, ProductCreated>()
+        .skip::<Product, CreateFailed>()
+        .skip::<Product, Updated>()
+        .skip::<Product, Archived>()
+        .skip::<Product, Restored>()
+        .skip::<Product, ProductDeleted>()
+        .skip::<Product, VariantAdded>()
+        .skip::<Product, VariantRemoved>()
+        .skip::<Product, PriceChanged>()
+        .skip::<Product, PriceScheduled>()
+        .skip::<Product, PriceUnscheduled>()
+        .handler(Reported(command_created()))
+        .handler(Reported(command_moved()))
+        .handler(Reported(command_deleted()))
+        .handler(Reported(command_category_assigned()))
+        .handler(Reported(command_category_unassigned()))
+}
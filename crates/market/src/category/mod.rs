@@ -0,0 +1,66 @@
+mod command;
+mod query;
+
+use evento::AggregatorName;
+use serde::{Deserialize, Serialize};
+
+pub use command::*;
+pub use query::*;
+
+use crate::RequestEvent;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Category {
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub owner_id: Option<String>,
+    pub deleted: bool,
+}
+
+#[evento::aggregator]
+impl Category {
+    async fn created(&mut self, event: RequestEvent<Created>) -> anyhow::Result<()> {
+        self.name = event.data.name;
+        self.parent_id = event.data.parent_id;
+        self.owner_id = event.metadata.user_owner_id;
+
+        Ok(())
+    }
+
+    async fn renamed(&mut self, event: RequestEvent<Renamed>) -> anyhow::Result<()> {
+        self.name = event.data.name;
+
+        Ok(())
+    }
+
+    async fn moved(&mut self, event: RequestEvent<Moved>) -> anyhow::Result<()> {
+        self.parent_id = event.data.parent_id;
+
+        Ok(())
+    }
+
+    async fn deleted(&mut self, _event: RequestEvent<Deleted>) -> anyhow::Result<()> {
+        self.deleted = true;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Created {
+    pub name: String,
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Renamed {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Moved {
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Deleted {}
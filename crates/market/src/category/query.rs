@@ -0,0 +1,183 @@
+use crate::{
+    RequestEvent,
+    category::{Category, Created, Deleted, Moved, Renamed},
+};
+use evento::{AggregatorName, SubscribeBuilder, sql::Reader};
+use sea_query::{Expr, ExprTrait, Query};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use timada_shared::{QueryPool, Reported, format_created_at};
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryCategory {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub owner_id: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryCategoryCursor {
+    pub i: String,
+    pub c: String,
+}
+
+impl evento::cursor::Cursor for QueryCategory {
+    type T = QueryCategoryCursor;
+
+    fn serialize(&self) -> Self::T {
+        Self::T {
+            i: self.id.to_owned(),
+            c: self.created_at.to_owned(),
+        }
+    }
+}
+
+impl evento::sql::Bind for QueryCategory {
+    type T = QueryCategoryIden;
+    type I = [Self::T; 2];
+    type V = [Expr; 2];
+    type Cursor = Self;
+
+    fn columns() -> Self::I {
+        [QueryCategoryIden::CreatedAt, QueryCategoryIden::Id]
+    }
+
+    fn values(
+        cursor: <<Self as evento::sql::Bind>::Cursor as evento::cursor::Cursor>::T,
+    ) -> Self::V {
+        [cursor.c.into(), cursor.i.into()]
+    }
+}
+
+#[evento::handler(Category)]
+async fn categories_created<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Created>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::insert()
+        .into_table(QueryCategoryIden::Table)
+        .columns([
+            QueryCategoryIden::Id,
+            QueryCategoryIden::Name,
+            QueryCategoryIden::ParentId,
+            QueryCategoryIden::OwnerId,
+            QueryCategoryIden::CreatedAt,
+        ])
+        .values_panic([
+            event.aggregator_id.to_owned().into(),
+            event.data.name.to_owned().into(),
+            event.data.parent_id.to_owned().into(),
+            event.metadata.user_owner_id.to_owned().into(),
+            format_created_at(event.timestamp).into(),
+        ])
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Category)]
+async fn categories_renamed<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Renamed>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryCategoryIden::Table)
+        .values([(QueryCategoryIden::Name, event.data.name.to_owned().into())])
+        .and_where(Expr::col(QueryCategoryIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Category)]
+async fn categories_moved<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Moved>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryCategoryIden::Table)
+        .values([(
+            QueryCategoryIden::ParentId,
+            event.data.parent_id.to_owned().into(),
+        )])
+        .and_where(Expr::col(QueryCategoryIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Category)]
+async fn categories_deleted<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Deleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(QueryCategoryIden::Table)
+        .and_where(Expr::col(QueryCategoryIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+/// Only categories of `owner_id` are read, categories without owner when it is `None`.
+pub async fn query_categories(
+    pool: &QueryPool,
+    owner_id: Option<&str>,
+) -> anyhow::Result<evento::cursor::ReadResult<QueryCategory>> {
+    let statement = Query::select()
+        .columns([
+            QueryCategoryIden::Id,
+            QueryCategoryIden::Name,
+            QueryCategoryIden::ParentId,
+            QueryCategoryIden::OwnerId,
+            QueryCategoryIden::CreatedAt,
+        ])
+        .from(QueryCategoryIden::Table)
+        .and_where(match owner_id {
+            Some(owner_id) => Expr::col(QueryCategoryIden::OwnerId).eq(owner_id),
+            _ => Expr::col(QueryCategoryIden::OwnerId).is_null(),
+        })
+        .to_owned();
+
+    pool.read(Reader::new(statement).forward(1000, None)).await
+}
+
+pub async fn reset_query_categories(pool: &QueryPool) -> anyhow::Result<()> {
+    let statement = Query::delete()
+        .from_table(QueryCategoryIden::Table)
+        .to_owned();
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub fn subscribe_query_categories<E: evento::Executor + Clone>(
+    region: impl Into<String>,
+) -> anyhow::Result<SubscribeBuilder<E>> {
+    let region = region.into();
+
+    Ok(
+        evento::subscribe(format!("market.{region}.category.query.categories"))
+            .routing_key(region)
+            .aggregator::<Category>()
+            .handler(Reported(categories_created()))
+            .handler(Reported(categories_renamed()))
+            .handler(Reported(categories_moved()))
+            .handler(Reported(categories_deleted())),
+    )
+}
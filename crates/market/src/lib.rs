@@ -1,7 +1,10 @@
 use evento::EventDetails;
 use timada_shared::RequestMetadata;
 
+pub mod category;
 pub mod migrator;
+pub mod price;
 pub mod product;
+pub mod variant;
 
 pub(crate) type RequestEvent<D> = EventDetails<D, RequestMetadata>;
@@ -0,0 +1,92 @@
+use crate::product::CommandProductNameIden;
+use sea_query::{ColumnDef, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2025_08_16_04_17::Market202508160417;
+
+pub struct CreateProductNameTableOperation;
+
+impl Statements for CreateProductNameTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(CommandProductNameIden::Table)
+                .col(
+                    ColumnDef::new(CommandProductNameIden::Id)
+                        .string()
+                        .string_len(26)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CommandProductNameIden::RoutingKey)
+                        .string()
+                        .string_len(50)
+                        .not_null()
+                        .default(""),
+                )
+                .col(
+                    ColumnDef::new(CommandProductNameIden::OwnerId)
+                        .string()
+                        .string_len(26)
+                        .not_null()
+                        .default(""),
+                )
+                .col(
+                    ColumnDef::new(CommandProductNameIden::Name)
+                        .string()
+                        .string_len(50)
+                        .not_null(),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(CommandProductNameIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateProductNameIdxOperation;
+
+impl Statements for CreateProductNameIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_command_product_name")
+                .table(CommandProductNameIden::Table)
+                .col(CommandProductNameIden::RoutingKey)
+                .col(CommandProductNameIden::OwnerId)
+                .col(CommandProductNameIden::Name)
+                .unique()
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_command_product_name")
+                .table(CommandProductNameIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610170812;
+
+timada_shared::query_migration!(
+    Market202610170812,
+    "main",
+    "market_2026_10_17_08_12",
+    vec_box![Market202508160417],
+    vec_box![
+        CreateProductNameTableOperation,
+        CreateProductNameIdxOperation
+    ]
+);
@@ -0,0 +1,148 @@
+use crate::{category::QueryCategoryIden, product::QueryProductCategoryIden};
+use sea_query::{ColumnDef, Expr, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_17_08_12::Market202610170812;
+
+pub struct CreateCategoryTableOperation;
+
+impl Statements for CreateCategoryTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(QueryCategoryIden::Table)
+                .col(
+                    ColumnDef::new(QueryCategoryIden::Id)
+                        .string()
+                        .string_len(26)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryCategoryIden::Name)
+                        .string()
+                        .string_len(50)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryCategoryIden::ParentId)
+                        .string()
+                        .string_len(26),
+                )
+                .col(
+                    ColumnDef::new(QueryCategoryIden::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(QueryCategoryIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateCategoryParentIdxOperation;
+
+impl Statements for CreateCategoryParentIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_category_parent_id")
+                .table(QueryCategoryIden::Table)
+                .col(QueryCategoryIden::ParentId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_category_parent_id")
+                .table(QueryCategoryIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateProductCategoryTableOperation;
+
+impl Statements for CreateProductCategoryTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(QueryProductCategoryIden::Table)
+                .col(
+                    ColumnDef::new(QueryProductCategoryIden::ProductId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryProductCategoryIden::CategoryId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .primary_key(
+                    Index::create()
+                        .col(QueryProductCategoryIden::ProductId)
+                        .col(QueryProductCategoryIden::CategoryId),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(QueryProductCategoryIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateProductCategoryIdxOperation;
+
+impl Statements for CreateProductCategoryIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_category_category_id")
+                .table(QueryProductCategoryIden::Table)
+                .col(QueryProductCategoryIden::CategoryId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_category_category_id")
+                .table(QueryProductCategoryIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610170930;
+
+timada_shared::query_migration!(
+    Market202610170930,
+    "main",
+    "market_2026_10_17_09_30",
+    vec_box![Market202610170812],
+    vec_box![
+        CreateCategoryTableOperation,
+        CreateCategoryParentIdxOperation,
+        CreateProductCategoryTableOperation,
+        CreateProductCategoryIdxOperation
+    ]
+);
@@ -0,0 +1,117 @@
+use crate::variant::QueryVariantIden;
+use sea_query::{ColumnDef, Expr, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_17_09_30::Market202610170930;
+
+pub struct CreateVariantTableOperation;
+
+impl Statements for CreateVariantTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(QueryVariantIden::Table)
+                .col(
+                    ColumnDef::new(QueryVariantIden::Id)
+                        .string()
+                        .string_len(26)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryVariantIden::ProductId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryVariantIden::Sku)
+                        .string()
+                        .string_len(50)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryVariantIden::Name)
+                        .string()
+                        .string_len(100)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryVariantIden::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(QueryVariantIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateVariantProductIdxOperation;
+
+impl Statements for CreateVariantProductIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_variant_product_id")
+                .table(QueryVariantIden::Table)
+                .col(QueryVariantIden::ProductId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_variant_product_id")
+                .table(QueryVariantIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateVariantSkuIdxOperation;
+
+impl Statements for CreateVariantSkuIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_variant_sku")
+                .table(QueryVariantIden::Table)
+                .col(QueryVariantIden::Sku)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_variant_sku")
+                .table(QueryVariantIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610171105;
+
+timada_shared::query_migration!(
+    Market202610171105,
+    "main",
+    "market_2026_10_17_11_05",
+    vec_box![Market202610170930],
+    vec_box![
+        CreateVariantTableOperation,
+        CreateVariantProductIdxOperation,
+        CreateVariantSkuIdxOperation
+    ]
+);
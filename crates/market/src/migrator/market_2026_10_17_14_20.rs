@@ -0,0 +1,141 @@
+use crate::{product::QueryProductIden, variant::QueryVariantIden};
+use sea_query::{ColumnDef, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_17_11_05::Market202610171105;
+
+pub struct AddProductPriceAmountOperation;
+
+impl Statements for AddProductPriceAmountOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryProductIden::Table)
+                .add_column(ColumnDef::new(QueryProductIden::PriceAmount).big_integer())
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryProductIden::Table)
+                .drop_column(QueryProductIden::PriceAmount)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct AddProductPriceCurrencyOperation;
+
+impl Statements for AddProductPriceCurrencyOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryProductIden::Table)
+                .add_column(
+                    ColumnDef::new(QueryProductIden::PriceCurrency)
+                        .string()
+                        .string_len(3),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryProductIden::Table)
+                .drop_column(QueryProductIden::PriceCurrency)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateProductPriceIdxOperation;
+
+impl Statements for CreateProductPriceIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_price_amount")
+                .table(QueryProductIden::Table)
+                .col(QueryProductIden::PriceAmount)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_price_amount")
+                .table(QueryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct AddVariantPriceAmountOperation;
+
+impl Statements for AddVariantPriceAmountOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryVariantIden::Table)
+                .add_column(ColumnDef::new(QueryVariantIden::PriceAmount).big_integer())
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryVariantIden::Table)
+                .drop_column(QueryVariantIden::PriceAmount)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct AddVariantPriceCurrencyOperation;
+
+impl Statements for AddVariantPriceCurrencyOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryVariantIden::Table)
+                .add_column(
+                    ColumnDef::new(QueryVariantIden::PriceCurrency)
+                        .string()
+                        .string_len(3),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryVariantIden::Table)
+                .drop_column(QueryVariantIden::PriceCurrency)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610171420;
+
+timada_shared::query_migration!(
+    Market202610171420,
+    "main",
+    "market_2026_10_17_14_20",
+    vec_box![Market202610171105],
+    vec_box![
+        AddProductPriceAmountOperation,
+        AddProductPriceCurrencyOperation,
+        CreateProductPriceIdxOperation,
+        AddVariantPriceAmountOperation,
+        AddVariantPriceCurrencyOperation
+    ]
+);
@@ -0,0 +1,192 @@
+use crate::price::{QueryPriceHistoryIden, QueryScheduledPriceIden};
+use sea_query::{ColumnDef, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_17_14_20::Market202610171420;
+
+pub struct CreatePriceHistoryTableOperation;
+
+impl Statements for CreatePriceHistoryTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(QueryPriceHistoryIden::Table)
+                .col(
+                    ColumnDef::new(QueryPriceHistoryIden::Id)
+                        .string()
+                        .string_len(26)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryPriceHistoryIden::ProductId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryPriceHistoryIden::VariantId)
+                        .string()
+                        .string_len(26),
+                )
+                .col(
+                    ColumnDef::new(QueryPriceHistoryIden::Amount)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryPriceHistoryIden::Currency)
+                        .string()
+                        .string_len(3)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryPriceHistoryIden::ScheduleId)
+                        .string()
+                        .string_len(26),
+                )
+                .col(
+                    ColumnDef::new(QueryPriceHistoryIden::ChangedAt)
+                        .big_integer()
+                        .not_null(),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(QueryPriceHistoryIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreatePriceHistoryProductIdxOperation;
+
+impl Statements for CreatePriceHistoryProductIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_price_history_product_id")
+                .table(QueryPriceHistoryIden::Table)
+                .col(QueryPriceHistoryIden::ProductId)
+                .col(QueryPriceHistoryIden::ChangedAt)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_price_history_product_id")
+                .table(QueryPriceHistoryIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateScheduledPriceTableOperation;
+
+impl Statements for CreateScheduledPriceTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(QueryScheduledPriceIden::Table)
+                .col(
+                    ColumnDef::new(QueryScheduledPriceIden::Id)
+                        .string()
+                        .string_len(26)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryScheduledPriceIden::ProductId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryScheduledPriceIden::VariantId)
+                        .string()
+                        .string_len(26),
+                )
+                .col(
+                    ColumnDef::new(QueryScheduledPriceIden::Amount)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryScheduledPriceIden::Currency)
+                        .string()
+                        .string_len(3)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryScheduledPriceIden::StartsAt)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryScheduledPriceIden::UserId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(QueryScheduledPriceIden::UserOwnerId)
+                        .string()
+                        .string_len(26),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(QueryScheduledPriceIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateScheduledPriceStartsAtIdxOperation;
+
+impl Statements for CreateScheduledPriceStartsAtIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_scheduled_price_starts_at")
+                .table(QueryScheduledPriceIden::Table)
+                .col(QueryScheduledPriceIden::StartsAt)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_scheduled_price_starts_at")
+                .table(QueryScheduledPriceIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610171640;
+
+timada_shared::query_migration!(
+    Market202610171640,
+    "main",
+    "market_2026_10_17_16_40",
+    vec_box![Market202610171420],
+    vec_box![
+        CreatePriceHistoryTableOperation,
+        CreatePriceHistoryProductIdxOperation,
+        CreateScheduledPriceTableOperation,
+        CreateScheduledPriceStartsAtIdxOperation
+    ]
+);
@@ -0,0 +1,38 @@
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_17_16_40::Market202610171640;
+
+pub struct CreateProductFtsTableOperation;
+
+impl Statements for CreateProductFtsTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        // FTS5 only exists on sqlite, `market_2026_10_18_09_00` adds the other backends.
+        if backend != Backend::Sqlite {
+            return vec![];
+        }
+
+        vec![
+            r#"CREATE VIRTUAL TABLE "query_product_fts" USING fts5("id" UNINDEXED, "name", tokenize = 'unicode61 remove_diacritics 2')"#.to_owned(),
+            r#"INSERT INTO "query_product_fts" ("id", "name") SELECT "id", "name" FROM "query_product" WHERE "state" <> 'deleted'"#.to_owned(),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        if backend != Backend::Sqlite {
+            return vec![];
+        }
+
+        vec![r#"DROP TABLE "query_product_fts""#.to_owned()]
+    }
+}
+
+pub struct Market202610171945;
+
+timada_shared::query_migration!(
+    Market202610171945,
+    "main",
+    "market_2026_10_17_19_45",
+    vec_box![Market202610171640],
+    vec_box![CreateProductFtsTableOperation]
+);
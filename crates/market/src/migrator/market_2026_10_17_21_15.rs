@@ -0,0 +1,67 @@
+use crate::product::QueryProductIden;
+use sea_query::Index;
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_17_19_45::Market202610171945;
+
+pub struct CreateProductNameIdxOperation;
+
+impl Statements for CreateProductNameIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_name")
+                .table(QueryProductIden::Table)
+                .col(QueryProductIden::Name)
+                .col(QueryProductIden::Id)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_name")
+                .table(QueryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateProductCreatedAtIdxOperation;
+
+impl Statements for CreateProductCreatedAtIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_created_at")
+                .table(QueryProductIden::Table)
+                .col(QueryProductIden::CreatedAt)
+                .col(QueryProductIden::Id)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_created_at")
+                .table(QueryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610172115;
+
+timada_shared::query_migration!(
+    Market202610172115,
+    "main",
+    "market_2026_10_17_21_15",
+    vec_box![Market202610171945],
+    vec_box![
+        CreateProductNameIdxOperation,
+        CreateProductCreatedAtIdxOperation
+    ]
+);
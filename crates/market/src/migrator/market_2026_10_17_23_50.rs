@@ -0,0 +1,67 @@
+use crate::product::QueryProductIden;
+use sea_query::{ColumnDef, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_17_21_15::Market202610172115;
+
+pub struct AddProductOwnerIdOperation;
+
+impl Statements for AddProductOwnerIdOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryProductIden::Table)
+                .add_column(
+                    ColumnDef::new(QueryProductIden::OwnerId)
+                        .string()
+                        .string_len(26),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryProductIden::Table)
+                .drop_column(QueryProductIden::OwnerId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateProductOwnerIdIdxOperation;
+
+impl Statements for CreateProductOwnerIdIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_owner_id")
+                .table(QueryProductIden::Table)
+                .col(QueryProductIden::OwnerId)
+                .col(QueryProductIden::CreatedAt)
+                .col(QueryProductIden::Id)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_owner_id")
+                .table(QueryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610172350;
+
+timada_shared::query_migration!(
+    Market202610172350,
+    "main",
+    "market_2026_10_17_23_50",
+    vec_box![Market202610172115],
+    vec_box![AddProductOwnerIdOperation, CreateProductOwnerIdIdxOperation]
+);
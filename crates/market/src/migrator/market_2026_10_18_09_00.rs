@@ -0,0 +1,158 @@
+use crate::product::QueryProductIden;
+use sea_query::Index;
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, CreatedAtToStringOperation, Statements};
+
+use super::market_2026_10_17_23_50::Market202610172350;
+
+pub struct DropProductCreatedAtIdxOperation;
+
+impl Statements for DropProductCreatedAtIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_created_at")
+                .table(QueryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_created_at")
+                .table(QueryProductIden::Table)
+                .col(QueryProductIden::CreatedAt)
+                .col(QueryProductIden::Id)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateProductCreatedAtIdxOperation;
+
+impl Statements for CreateProductCreatedAtIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_created_at")
+                .table(QueryProductIden::Table)
+                .col(QueryProductIden::CreatedAt)
+                .col(QueryProductIden::Id)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_created_at")
+                .table(QueryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct DropProductOwnerIdIdxOperation;
+
+impl Statements for DropProductOwnerIdIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_owner_id")
+                .table(QueryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_owner_id")
+                .table(QueryProductIden::Table)
+                .col(QueryProductIden::OwnerId)
+                .col(QueryProductIden::CreatedAt)
+                .col(QueryProductIden::Id)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateProductOwnerIdIdxOperation;
+
+impl Statements for CreateProductOwnerIdIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_product_owner_id")
+                .table(QueryProductIden::Table)
+                .col(QueryProductIden::OwnerId)
+                .col(QueryProductIden::CreatedAt)
+                .col(QueryProductIden::Id)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_product_owner_id")
+                .table(QueryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+/// Full text table of MySQL and Postgres, sqlite has the FTS5 one of `market_2026_10_17_19_45`.
+pub struct CreateProductFtsTableOperation;
+
+impl Statements for CreateProductFtsTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        let statements: &[&str] = match backend {
+            Backend::Sqlite => &[],
+            Backend::MySql => &[
+                "CREATE TABLE `query_product_fts` (`id` varchar(26) NOT NULL PRIMARY KEY, `name` varchar(255) NOT NULL, FULLTEXT INDEX `idx_query_product_fts_name` (`name`))",
+                "INSERT INTO `query_product_fts` (`id`, `name`) SELECT `id`, `name` FROM `query_product` WHERE `state` <> 'deleted'",
+            ],
+            Backend::Postgres => &[
+                r#"CREATE TABLE "query_product_fts" ("id" varchar(26) NOT NULL PRIMARY KEY, "name" varchar NOT NULL)"#,
+                r#"CREATE INDEX "idx_query_product_fts_name" ON "query_product_fts" USING GIN (to_tsvector('simple', "name"))"#,
+                r#"INSERT INTO "query_product_fts" ("id", "name") SELECT "id", "name" FROM "query_product" WHERE "state" <> 'deleted'"#,
+            ],
+        };
+
+        statements
+            .iter()
+            .map(|statement| statement.to_string())
+            .collect()
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        let statement = match backend {
+            Backend::Sqlite => return vec![],
+            Backend::MySql => "DROP TABLE `query_product_fts`",
+            Backend::Postgres => r#"DROP TABLE "query_product_fts""#,
+        };
+
+        vec![statement.to_owned()]
+    }
+}
+
+pub struct Market202610180900;
+
+timada_shared::query_migration!(
+    Market202610180900,
+    "main",
+    "market_2026_10_18_09_00",
+    vec_box![Market202610172350],
+    vec_box![
+        DropProductCreatedAtIdxOperation,
+        DropProductOwnerIdIdxOperation,
+        CreatedAtToStringOperation("query_product"),
+        CreateProductCreatedAtIdxOperation,
+        CreateProductOwnerIdIdxOperation,
+        CreatedAtToStringOperation("query_category"),
+        CreatedAtToStringOperation("query_variant"),
+        CreateProductFtsTableOperation
+    ]
+);
@@ -0,0 +1,49 @@
+use crate::variant::QueryVariantIden;
+use sea_query::Index;
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_18_09_00::Market202610180900;
+
+pub struct UniqueVariantSkuIdxOperation;
+
+impl Statements for UniqueVariantSkuIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_variant_sku")
+                .table(QueryVariantIden::Table)
+                .build_any(backend.schema_builder()),
+            Index::create()
+                .name("idx_query_variant_sku")
+                .table(QueryVariantIden::Table)
+                .col(QueryVariantIden::Sku)
+                .unique()
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_variant_sku")
+                .table(QueryVariantIden::Table)
+                .build_any(backend.schema_builder()),
+            Index::create()
+                .name("idx_query_variant_sku")
+                .table(QueryVariantIden::Table)
+                .col(QueryVariantIden::Sku)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610180930;
+
+timada_shared::query_migration!(
+    Market202610180930,
+    "main",
+    "market_2026_10_18_09_30",
+    vec_box![Market202610180900],
+    vec_box![UniqueVariantSkuIdxOperation]
+);
@@ -0,0 +1,71 @@
+use crate::category::QueryCategoryIden;
+use sea_query::{ColumnDef, Index, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_18_09_30::Market202610180930;
+
+/// Left empty on existing rows, rebuilding the categories projection fills it from the events.
+pub struct AddCategoryOwnerIdOperation;
+
+impl Statements for AddCategoryOwnerIdOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryCategoryIden::Table)
+                .add_column(
+                    ColumnDef::new(QueryCategoryIden::OwnerId)
+                        .string()
+                        .string_len(26),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::alter()
+                .table(QueryCategoryIden::Table)
+                .drop_column(QueryCategoryIden::OwnerId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateCategoryOwnerIdIdxOperation;
+
+impl Statements for CreateCategoryOwnerIdIdxOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::create()
+                .name("idx_query_category_owner_id")
+                .table(QueryCategoryIden::Table)
+                .col(QueryCategoryIden::OwnerId)
+                .col(QueryCategoryIden::CreatedAt)
+                .col(QueryCategoryIden::Id)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Index::drop()
+                .name("idx_query_category_owner_id")
+                .table(QueryCategoryIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct Market202610181000;
+
+timada_shared::query_migration!(
+    Market202610181000,
+    "main",
+    "market_2026_10_18_10_00",
+    vec_box![Market202610180930],
+    vec_box![
+        AddCategoryOwnerIdOperation,
+        CreateCategoryOwnerIdIdxOperation
+    ]
+);
@@ -0,0 +1,150 @@
+use crate::{
+    category::{CommandCategoryIden, CommandCategoryProductIden, QueryCategoryIden},
+    product::QueryProductCategoryIden,
+};
+use sea_query::{
+    ColumnDef, Index, MysqlQueryBuilder, PostgresQueryBuilder, Query, SqliteQueryBuilder, Table,
+};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_18_10_00::Market202610181000;
+
+pub struct CreateCategoryTableOperation;
+
+impl Statements for CreateCategoryTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(CommandCategoryIden::Table)
+                .col(
+                    ColumnDef::new(CommandCategoryIden::Id)
+                        .string()
+                        .string_len(26)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CommandCategoryIden::ParentId)
+                        .string()
+                        .string_len(26),
+                )
+                .build_any(backend.schema_builder()),
+            Index::create()
+                .name("idx_command_category_parent_id")
+                .table(CommandCategoryIden::Table)
+                .col(CommandCategoryIden::ParentId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(CommandCategoryIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+pub struct CreateCategoryProductTableOperation;
+
+impl Statements for CreateCategoryProductTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(CommandCategoryProductIden::Table)
+                .col(
+                    ColumnDef::new(CommandCategoryProductIden::CategoryId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CommandCategoryProductIden::ProductId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .primary_key(
+                    Index::create()
+                        .col(CommandCategoryProductIden::CategoryId)
+                        .col(CommandCategoryProductIden::ProductId),
+                )
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(CommandCategoryProductIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+/// The category command subscription only reads its own tables, they start from the rows of
+/// the projections as its past events are not replayed.
+pub struct FillCategoryTablesOperation;
+
+impl Statements for FillCategoryTablesOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        let categories = Query::insert()
+            .into_table(CommandCategoryIden::Table)
+            .columns([CommandCategoryIden::Id, CommandCategoryIden::ParentId])
+            .select_from(
+                Query::select()
+                    .columns([QueryCategoryIden::Id, QueryCategoryIden::ParentId])
+                    .from(QueryCategoryIden::Table)
+                    .to_owned(),
+            )
+            .expect("columns and values match")
+            .to_owned();
+
+        let products = Query::insert()
+            .into_table(CommandCategoryProductIden::Table)
+            .columns([
+                CommandCategoryProductIden::CategoryId,
+                CommandCategoryProductIden::ProductId,
+            ])
+            .select_from(
+                Query::select()
+                    .columns([
+                        QueryProductCategoryIden::CategoryId,
+                        QueryProductCategoryIden::ProductId,
+                    ])
+                    .from(QueryProductCategoryIden::Table)
+                    .to_owned(),
+            )
+            .expect("columns and values match")
+            .to_owned();
+
+        [categories, products]
+            .iter()
+            .map(|statement| match backend {
+                Backend::Sqlite => statement.to_string(SqliteQueryBuilder),
+                Backend::MySql => statement.to_string(MysqlQueryBuilder),
+                Backend::Postgres => statement.to_string(PostgresQueryBuilder),
+            })
+            .collect()
+    }
+
+    fn down(&self, _backend: Backend) -> Vec<String> {
+        vec![]
+    }
+}
+
+pub struct Market202610181030;
+
+timada_shared::query_migration!(
+    Market202610181030,
+    "main",
+    "market_2026_10_18_10_30",
+    vec_box![Market202610181000],
+    vec_box![
+        CreateCategoryTableOperation,
+        CreateCategoryProductTableOperation,
+        FillCategoryTablesOperation
+    ]
+);
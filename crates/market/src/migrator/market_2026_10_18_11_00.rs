@@ -0,0 +1,104 @@
+use crate::{product::CommandVariantSkuIden, variant::QueryVariantIden};
+use sea_query::{
+    ColumnDef, Index, MysqlQueryBuilder, PostgresQueryBuilder, Query, SqliteQueryBuilder, Table,
+};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+use super::market_2026_10_18_10_30::Market202610181030;
+
+pub struct CreateVariantSkuTableOperation;
+
+impl Statements for CreateVariantSkuTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::create()
+                .table(CommandVariantSkuIden::Table)
+                .col(
+                    ColumnDef::new(CommandVariantSkuIden::Sku)
+                        .string()
+                        .string_len(50)
+                        .primary_key()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CommandVariantSkuIden::ProductId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CommandVariantSkuIden::VariantId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .build_any(backend.schema_builder()),
+            Index::create()
+                .name("idx_command_variant_sku_product_id")
+                .table(CommandVariantSkuIden::Table)
+                .col(CommandVariantSkuIden::ProductId)
+                .build_any(backend.schema_builder()),
+            Index::create()
+                .name("idx_command_variant_sku_variant_id")
+                .table(CommandVariantSkuIden::Table)
+                .col(CommandVariantSkuIden::VariantId)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![
+            Table::drop()
+                .table(CommandVariantSkuIden::Table)
+                .build_any(backend.schema_builder()),
+        ]
+    }
+}
+
+/// Skus of the variants added before the product command subscription handled them.
+pub struct FillVariantSkuTableOperation;
+
+impl Statements for FillVariantSkuTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        let statement = Query::insert()
+            .into_table(CommandVariantSkuIden::Table)
+            .columns([
+                CommandVariantSkuIden::Sku,
+                CommandVariantSkuIden::ProductId,
+                CommandVariantSkuIden::VariantId,
+            ])
+            .select_from(
+                Query::select()
+                    .columns([
+                        QueryVariantIden::Sku,
+                        QueryVariantIden::ProductId,
+                        QueryVariantIden::Id,
+                    ])
+                    .from(QueryVariantIden::Table)
+                    .to_owned(),
+            )
+            .expect("columns and values match")
+            .to_owned();
+
+        vec![match backend {
+            Backend::Sqlite => statement.to_string(SqliteQueryBuilder),
+            Backend::MySql => statement.to_string(MysqlQueryBuilder),
+            Backend::Postgres => statement.to_string(PostgresQueryBuilder),
+        }]
+    }
+
+    fn down(&self, _backend: Backend) -> Vec<String> {
+        vec![]
+    }
+}
+
+pub struct Market202610181100;
+
+timada_shared::query_migration!(
+    Market202610181100,
+    "main",
+    "market_2026_10_18_11_00",
+    vec_box![Market202610181030],
+    vec_box![CreateVariantSkuTableOperation, FillVariantSkuTableOperation]
+);
@@ -1,10 +1,49 @@
 mod market_2025_08_16_04_17;
+mod market_2026_10_17_08_12;
+mod market_2026_10_17_09_30;
+mod market_2026_10_17_11_05;
+mod market_2026_10_17_14_20;
+mod market_2026_10_17_16_40;
+mod market_2026_10_17_19_45;
+mod market_2026_10_17_21_15;
+mod market_2026_10_17_23_50;
+mod market_2026_10_18_09_00;
+mod market_2026_10_18_09_30;
+mod market_2026_10_18_10_00;
+mod market_2026_10_18_10_30;
+mod market_2026_10_18_11_00;
 
 use market_2025_08_16_04_17::Market202508160417;
-use sqlx_migrator::{Info, Migrator};
+use market_2026_10_17_08_12::Market202610170812;
+use market_2026_10_17_09_30::Market202610170930;
+use market_2026_10_17_11_05::Market202610171105;
+use market_2026_10_17_14_20::Market202610171420;
+use market_2026_10_17_16_40::Market202610171640;
+use market_2026_10_17_19_45::Market202610171945;
+use market_2026_10_17_21_15::Market202610172115;
+use market_2026_10_17_23_50::Market202610172350;
+use market_2026_10_18_09_00::Market202610180900;
+use market_2026_10_18_09_30::Market202610180930;
+use market_2026_10_18_10_00::Market202610181000;
+use market_2026_10_18_10_30::Market202610181030;
+use market_2026_10_18_11_00::Market202610181100;
+use timada_shared::QueryMigrator;
 
-pub fn add_migrations(migrator: &mut Migrator<sqlx::Sqlite>) -> Result<(), sqlx_migrator::Error> {
-    migrator.add_migration(Box::new(Market202508160417))?;
+pub fn add_migrations(migrator: &mut QueryMigrator) -> Result<(), sqlx_migrator::Error> {
+    migrator.add_migration(Market202508160417)?;
+    migrator.add_migration(Market202610170812)?;
+    migrator.add_migration(Market202610170930)?;
+    migrator.add_migration(Market202610171105)?;
+    migrator.add_migration(Market202610171420)?;
+    migrator.add_migration(Market202610171640)?;
+    migrator.add_migration(Market202610171945)?;
+    migrator.add_migration(Market202610172115)?;
+    migrator.add_migration(Market202610172350)?;
+    migrator.add_migration(Market202610180900)?;
+    migrator.add_migration(Market202610180930)?;
+    migrator.add_migration(Market202610181000)?;
+    migrator.add_migration(Market202610181030)?;
+    migrator.add_migration(Market202610181100)?;
 
     Ok(())
 }
@@ -0,0 +1,29 @@
+mod query;
+mod scheduler;
+
+use serde::{Deserialize, Serialize};
+use timada_shared::Money;
+use utoipa::ToSchema;
+
+pub use query::*;
+pub use scheduler::*;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct ScheduledPrice {
+    pub id: String,
+    pub variant_id: Option<String>,
+    pub price: Money,
+    pub starts_at: i64,
+}
+
+impl ScheduledPrice {
+    pub fn starts_at_utc(&self) -> String {
+        format_timestamp(self.starts_at)
+    }
+}
+
+pub fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,302 @@
+use crate::{
+    RequestEvent,
+    product::{
+        Archived, CategoryAssigned, CategoryUnassigned, CreateFailed, CreateRequested, Created,
+        Deleted, PriceChanged, PriceScheduled, PriceUnscheduled, Product, Restored, Updated,
+        VariantAdded, VariantRemoved,
+    },
+};
+use evento::{AggregatorName, SubscribeBuilder, sql::Reader};
+use sea_query::{Expr, ExprTrait, IntoTableRef, OnConflict, Order, Query};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use timada_shared::{Money, QueryPool, Reported};
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryPriceHistory {
+    pub id: String,
+    pub product_id: String,
+    pub variant_id: Option<String>,
+    pub amount: i64,
+    pub currency: String,
+    pub schedule_id: Option<String>,
+    pub changed_at: i64,
+}
+
+impl QueryPriceHistory {
+    pub fn price(&self) -> Money {
+        Money {
+            amount: self.amount,
+            currency: self.currency.to_owned(),
+        }
+    }
+
+    pub fn changed_at_utc(&self) -> String {
+        super::format_timestamp(self.changed_at)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryPriceHistoryCursor {
+    pub i: String,
+    pub c: i64,
+}
+
+impl evento::cursor::Cursor for QueryPriceHistory {
+    type T = QueryPriceHistoryCursor;
+
+    fn serialize(&self) -> Self::T {
+        Self::T {
+            i: self.id.to_owned(),
+            c: self.changed_at,
+        }
+    }
+}
+
+impl evento::sql::Bind for QueryPriceHistory {
+    type T = QueryPriceHistoryIden;
+    type I = [Self::T; 2];
+    type V = [Expr; 2];
+    type Cursor = Self;
+
+    fn columns() -> Self::I {
+        [QueryPriceHistoryIden::ChangedAt, QueryPriceHistoryIden::Id]
+    }
+
+    fn values(
+        cursor: <<Self as evento::sql::Bind>::Cursor as evento::cursor::Cursor>::T,
+    ) -> Self::V {
+        [cursor.c.into(), cursor.i.into()]
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryScheduledPrice {
+    pub id: String,
+    pub product_id: String,
+    pub variant_id: Option<String>,
+    pub amount: i64,
+    pub currency: String,
+    pub starts_at: i64,
+    pub user_id: String,
+    pub user_owner_id: Option<String>,
+}
+
+#[evento::handler(Product)]
+async fn prices_price_changed<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<PriceChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::insert()
+        .into_table(QueryPriceHistoryIden::Table)
+        .columns([
+            QueryPriceHistoryIden::Id,
+            QueryPriceHistoryIden::ProductId,
+            QueryPriceHistoryIden::VariantId,
+            QueryPriceHistoryIden::Amount,
+            QueryPriceHistoryIden::Currency,
+            QueryPriceHistoryIden::ScheduleId,
+            QueryPriceHistoryIden::ChangedAt,
+        ])
+        .values_panic([
+            event.id.to_string().into(),
+            event.aggregator_id.to_owned().into(),
+            event.data.variant_id.to_owned().into(),
+            event.data.price.amount.into(),
+            event.data.price.currency.to_owned().into(),
+            event.data.schedule_id.to_owned().into(),
+            event.timestamp.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(QueryPriceHistoryIden::Id)
+                .do_nothing_on([QueryPriceHistoryIden::Id])
+                .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    let Some(schedule_id) = &event.data.schedule_id else {
+        return Ok(());
+    };
+
+    let statement = Query::delete()
+        .from_table(QueryScheduledPriceIden::Table)
+        .and_where(Expr::col(QueryScheduledPriceIden::Id).eq(schedule_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn prices_price_scheduled<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<PriceScheduled>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let schedule = &event.data.schedule;
+    let statement = Query::insert()
+        .into_table(QueryScheduledPriceIden::Table)
+        .columns([
+            QueryScheduledPriceIden::Id,
+            QueryScheduledPriceIden::ProductId,
+            QueryScheduledPriceIden::VariantId,
+            QueryScheduledPriceIden::Amount,
+            QueryScheduledPriceIden::Currency,
+            QueryScheduledPriceIden::StartsAt,
+            QueryScheduledPriceIden::UserId,
+            QueryScheduledPriceIden::UserOwnerId,
+        ])
+        .values_panic([
+            schedule.id.to_owned().into(),
+            event.aggregator_id.to_owned().into(),
+            schedule.variant_id.to_owned().into(),
+            schedule.price.amount.into(),
+            schedule.price.currency.to_owned().into(),
+            schedule.starts_at.into(),
+            event.metadata.user_id.to_owned().into(),
+            event.metadata.user_owner_id.to_owned().into(),
+        ])
+        .on_conflict(
+            OnConflict::column(QueryScheduledPriceIden::Id)
+                .do_nothing_on([QueryScheduledPriceIden::Id])
+                .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn prices_price_unscheduled<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<PriceUnscheduled>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(QueryScheduledPriceIden::Table)
+        .and_where(Expr::col(QueryScheduledPriceIden::Id).eq(event.data.schedule_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn prices_deleted<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Deleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+
+    for statement in [
+        Query::delete()
+            .from_table(QueryScheduledPriceIden::Table)
+            .and_where(
+                Expr::col(QueryScheduledPriceIden::ProductId).eq(event.aggregator_id.to_owned()),
+            )
+            .to_owned(),
+        Query::delete()
+            .from_table(QueryPriceHistoryIden::Table)
+            .and_where(
+                Expr::col(QueryPriceHistoryIden::ProductId).eq(event.aggregator_id.to_owned()),
+            )
+            .to_owned(),
+    ] {
+        pool.execute(&statement).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn query_price_history(
+    pool: &QueryPool,
+    product_id: impl Into<String>,
+) -> anyhow::Result<evento::cursor::ReadResult<QueryPriceHistory>> {
+    let statement = Query::select()
+        .columns([
+            QueryPriceHistoryIden::Id,
+            QueryPriceHistoryIden::ProductId,
+            QueryPriceHistoryIden::VariantId,
+            QueryPriceHistoryIden::Amount,
+            QueryPriceHistoryIden::Currency,
+            QueryPriceHistoryIden::ScheduleId,
+            QueryPriceHistoryIden::ChangedAt,
+        ])
+        .from(QueryPriceHistoryIden::Table)
+        .and_where(Expr::col(QueryPriceHistoryIden::ProductId).eq(product_id.into()))
+        .to_owned();
+
+    pool.read(Reader::new(statement).desc().forward(100, None))
+        .await
+}
+
+pub async fn query_due_prices(
+    pool: &QueryPool,
+    now: i64,
+) -> anyhow::Result<Vec<QueryScheduledPrice>> {
+    let statement = Query::select()
+        .columns([
+            QueryScheduledPriceIden::Id,
+            QueryScheduledPriceIden::ProductId,
+            QueryScheduledPriceIden::VariantId,
+            QueryScheduledPriceIden::Amount,
+            QueryScheduledPriceIden::Currency,
+            QueryScheduledPriceIden::StartsAt,
+            QueryScheduledPriceIden::UserId,
+            QueryScheduledPriceIden::UserOwnerId,
+        ])
+        .from(QueryScheduledPriceIden::Table)
+        .and_where(Expr::col(QueryScheduledPriceIden::StartsAt).lte(now))
+        .order_by(QueryScheduledPriceIden::StartsAt, Order::Asc)
+        .limit(100)
+        .to_owned();
+
+    pool.fetch_all(&statement).await
+}
+
+pub async fn reset_query_prices(pool: &QueryPool) -> anyhow::Result<()> {
+    for table in [
+        QueryPriceHistoryIden::Table.into_table_ref(),
+        QueryScheduledPriceIden::Table.into_table_ref(),
+    ] {
+        let statement = Query::delete().from_table(table).to_owned();
+        pool.execute(&statement).await?;
+    }
+
+    Ok(())
+}
+
+pub fn subscribe_query_prices<E: evento::Executor + Clone>(
+    region: impl Into<String>,
+) -> anyhow::Result<SubscribeBuilder<E>> {
+    let region = region.into();
+
+    Ok(
+        evento::subscribe(format!("market.{region}.product.query.prices"))
+            .routing_key(region)
+            .aggregator::<Product>()
+            .skip::<Product, CreateRequested>()
+            .skip::<Product, CreateFailed>()
+            .skip::<Product, Created>()
+            .skip::<Product, Updated>()
+            .skip::<Product, Archived>()
+            .skip::<Product, Restored>()
+            .skip::<Product, CategoryAssigned>()
+            .skip::<Product, CategoryUnassigned>()
+            .skip::<Product, VariantAdded>()
+            .skip::<Product, VariantRemoved>()
+            .handler(Reported(prices_price_changed()))
+            .handler(Reported(prices_price_scheduled()))
+            .handler(Reported(prices_price_unscheduled()))
+            .handler(Reported(prices_deleted())),
+    )
+}
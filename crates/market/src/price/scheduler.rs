@@ -0,0 +1,51 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use timada_shared::{QueryPool, RequestMetadata};
+
+use crate::price::{QueryScheduledPrice, query_due_prices};
+
+/// Applies every scheduled price whose start time has passed, returns how many were handled.
+pub async fn apply_due_prices<E: evento::Executor>(
+    executor: &E,
+    pool: &QueryPool,
+) -> anyhow::Result<usize> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let schedules = query_due_prices(pool, now).await?;
+    let mut applied = 0;
+
+    // A failing schedule is left due, the next tick retries it after the others are applied.
+    for schedule in schedules {
+        match apply_due_price(executor, &schedule).await {
+            Ok(true) => applied += 1,
+            Ok(false) => {}
+            Err(err) => tracing::error!(
+                "apply_due_prices product='{}' schedule='{}' '{err}'",
+                schedule.product_id,
+                schedule.id
+            ),
+        }
+    }
+
+    Ok(applied)
+}
+
+async fn apply_due_price<E: evento::Executor>(
+    executor: &E,
+    schedule: &QueryScheduledPrice,
+) -> anyhow::Result<bool> {
+    let Some(builder) =
+        crate::product::apply_scheduled_price(executor, &schedule.product_id, &schedule.id).await?
+    else {
+        return Ok(false);
+    };
+
+    builder
+        .metadata(&RequestMetadata {
+            id: ulid::Ulid::new().to_string(),
+            user_id: schedule.user_id.to_owned(),
+            user_owner_id: schedule.user_owner_id.to_owned(),
+        })?
+        .commit(executor)
+        .await?;
+
+    Ok(true)
+}
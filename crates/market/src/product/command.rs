@@ -1,15 +1,46 @@
 use evento::{AggregatorName, SubscribeBuilder};
+use sea_query::{Expr, ExprTrait, OnConflict, Order, Query};
 use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use timada_shared::{Conflict, Money, NotFound, QueryPool, Reported, invalid_field};
+use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::{
     RequestEvent,
-    product::{CreateFailed, CreateRequested, Created, Product},
+    category::Category,
+    price::ScheduledPrice,
+    product::{
+        Archived, CategoryAssigned, CategoryUnassigned, CreateFailed, CreateRequested, Created,
+        Deleted, PriceChanged, PriceScheduled, PriceUnscheduled, Product, Restored, Updated,
+        VariantAdded, VariantRemoved,
+    },
+    variant::{self, Variant},
 };
 
-#[derive(Validate, Deserialize)]
+#[sea_query::enum_def]
+pub struct CommandProductName {
+    pub id: String,
+    pub routing_key: String,
+    pub owner_id: String,
+    pub name: String,
+}
+
+/// Variant holding a sku, skus are unique by region.
+#[sea_query::enum_def]
+pub struct CommandVariantSku {
+    pub sku: String,
+    pub product_id: String,
+    pub variant_id: String,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
 pub struct CreateInput {
     #[validate(length(min = 3, max = 25))]
+    #[schema(min_length = 3, max_length = 25)]
     pub name: String,
 }
 
@@ -22,15 +53,541 @@ pub fn create(input: CreateInput) -> anyhow::Result<evento::SaveBuilder<Product>
     })?)
 }
 
+#[derive(Validate, Deserialize)]
+pub struct UpdateInput {
+    #[validate(length(min = 3, max = 25))]
+    pub name: String,
+}
+
+pub async fn update<E: evento::Executor>(
+    executor: &E,
+    pool: &QueryPool,
+    id: impl Into<String>,
+    input: UpdateInput,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    input.validate()?;
+
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if product.item.state != super::ProductState::Ready {
+        anyhow::bail!(Conflict::new("product not ready"));
+    }
+
+    let name_id = find_product_name_id(
+        pool,
+        product.event.routing_key.to_owned().unwrap_or_default(),
+        product.item.owner_id.to_owned().unwrap_or_default(),
+        input.name.trim().to_lowercase(),
+    )
+    .await?;
+
+    if name_id.is_some_and(|id| id != product.event.aggregator_id) {
+        anyhow::bail!(Conflict::new("product name already exists"));
+    }
+
+    Ok(evento::save_with(product).data(&Updated { name: input.name })?)
+}
+
+pub async fn archive<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if product.item.state != super::ProductState::Ready {
+        anyhow::bail!(Conflict::new("product not ready"));
+    }
+
+    Ok(evento::save_with(product).data(&Archived {
+        state: super::ProductState::Archived,
+    })?)
+}
+
+pub async fn restore<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if product.item.state != super::ProductState::Archived {
+        anyhow::bail!(Conflict::new("product not archived"));
+    }
+
+    Ok(evento::save_with(product).data(&Restored {
+        state: super::ProductState::Ready,
+    })?)
+}
+
+pub async fn delete<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if !matches!(
+        product.item.state,
+        super::ProductState::Archived | super::ProductState::Failed
+    ) {
+        anyhow::bail!(Conflict::new(
+            "product must be archived or failed to be deleted"
+        ));
+    }
+
+    Ok(evento::save_with(product).data(&Deleted {
+        state: super::ProductState::Deleted,
+    })?)
+}
+
+#[derive(Deserialize)]
+pub struct AssignCategoryInput {
+    pub category_id: String,
+}
+
+pub async fn assign_category<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: AssignCategoryInput,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if product.item.state != super::ProductState::Ready {
+        anyhow::bail!(Conflict::new("product not ready"));
+    }
+
+    if product.item.category_ids.contains(&input.category_id) {
+        anyhow::bail!(Conflict::new("category already assigned"));
+    }
+
+    let category = evento::load::<Category, _>(executor, &input.category_id).await?;
+    if category.item.owner_id != product.item.owner_id {
+        anyhow::bail!(NotFound::new("category not found"));
+    }
+
+    if category.item.deleted {
+        anyhow::bail!(Conflict::new("category deleted"));
+    }
+
+    Ok(evento::save_with(product).data(&CategoryAssigned {
+        category_id: input.category_id,
+    })?)
+}
+
+pub async fn unassign_category<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: AssignCategoryInput,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if !product.item.category_ids.contains(&input.category_id) {
+        anyhow::bail!(Conflict::new("category not assigned"));
+    }
+
+    Ok(evento::save_with(product).data(&CategoryUnassigned {
+        category_id: input.category_id,
+    })?)
+}
+
+#[derive(Validate, Deserialize)]
+pub struct AddVariantsInput {
+    #[validate(length(min = 1, max = 500))]
+    pub options: String,
+}
+
+pub async fn add_variants<E: evento::Executor>(
+    executor: &E,
+    pool: &QueryPool,
+    id: impl Into<String>,
+    input: AddVariantsInput,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    input.validate()?;
+
+    let options = variant::parse_options(&input.options)?;
+    let combinations = variant::generate(&options);
+    if combinations.len() > 100 {
+        anyhow::bail!(invalid_field(
+            "options",
+            "a product cannot have more than 100 variants"
+        ));
+    }
+
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if product.item.state != super::ProductState::Ready {
+        anyhow::bail!(Conflict::new("product not ready"));
+    }
+
+    let mut builder = evento::save_with(product.clone());
+    let mut added = 0;
+    let mut skus = product
+        .item
+        .variants
+        .iter()
+        .map(|variant| variant.sku.to_owned())
+        .collect::<HashSet<_>>();
+
+    for options in combinations {
+        if product
+            .item
+            .variants
+            .iter()
+            .any(|variant| variant.options == options)
+        {
+            continue;
+        }
+
+        let sku = variant::sku(&product.event.aggregator_id, &product.item.name, &options);
+        if !skus.insert(sku.to_owned()) {
+            anyhow::bail!(Conflict::new(format!("variant sku '{sku}' already exists")));
+        }
+
+        builder = builder.data(&VariantAdded {
+            variant: Variant {
+                id: ulid::Ulid::new().to_string(),
+                sku,
+                options,
+                price: None,
+            },
+        })?;
+        added += 1;
+    }
+
+    if added == 0 {
+        anyhow::bail!(Conflict::new("variants already exist"));
+    }
+
+    if product.item.variants.len() + added > 100 {
+        anyhow::bail!(Conflict::new(
+            "a product cannot have more than 100 variants"
+        ));
+    }
+
+    let statement = Query::select()
+        .column(CommandVariantSkuIden::Sku)
+        .from(CommandVariantSkuIden::Table)
+        .and_where(Expr::col(CommandVariantSkuIden::Sku).is_in(skus))
+        .and_where(
+            Expr::col(CommandVariantSkuIden::ProductId).ne(product.event.aggregator_id.to_owned()),
+        )
+        .limit(1)
+        .to_owned();
+
+    if let Some((sku,)) = pool.fetch_optional::<(String,), _>(&statement).await? {
+        anyhow::bail!(Conflict::new(format!("variant sku '{sku}' already exists")));
+    }
+
+    Ok(builder)
+}
+
+#[derive(Deserialize)]
+pub struct RemoveVariantInput {
+    pub variant_id: String,
+}
+
+pub async fn remove_variant<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: RemoveVariantInput,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if !product
+        .item
+        .variants
+        .iter()
+        .any(|variant| variant.id == input.variant_id)
+    {
+        anyhow::bail!(NotFound::new("variant not found"));
+    }
+
+    Ok(evento::save_with(product).data(&VariantRemoved {
+        variant_id: input.variant_id,
+    })?)
+}
+
+#[derive(Deserialize)]
+pub struct ChangePriceInput {
+    pub variant_id: Option<String>,
+    pub amount: String,
+    pub currency: String,
+}
+
+pub async fn change_price<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: ChangePriceInput,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let price = Money::parse(&input.amount, input.currency)?;
+    if price.amount < 0 {
+        anyhow::bail!(invalid_field("amount", "price cannot be negative"));
+    }
+
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if product.item.state != super::ProductState::Ready {
+        anyhow::bail!(Conflict::new("product not ready"));
+    }
+
+    let variant_id = input.variant_id.filter(|id| !id.is_empty());
+    if let Some(variant_id) = &variant_id
+        && !product
+            .item
+            .variants
+            .iter()
+            .any(|variant| &variant.id == variant_id)
+    {
+        anyhow::bail!(NotFound::new("variant not found"));
+    }
+
+    Ok(evento::save_with(product).data(&PriceChanged {
+        variant_id,
+        price,
+        schedule_id: None,
+    })?)
+}
+
+#[derive(Deserialize)]
+pub struct SchedulePriceInput {
+    pub variant_id: Option<String>,
+    pub amount: String,
+    pub currency: String,
+    pub starts_at: String,
+}
+
+/// Start of a scheduled price as a timestamp, the date must carry its UTC offset, a date
+/// without one would be read in the time zone of the server.
+fn parse_starts_at(value: &str) -> anyhow::Result<i64> {
+    let value = value.trim();
+    let starts_at = chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .or_else(|| {
+            [
+                "%Y-%m-%dT%H:%M%:z",
+                "%Y-%m-%d %H:%M%:z",
+                "%Y-%m-%d %H:%M:%S%:z",
+            ]
+            .iter()
+            .find_map(|fmt| chrono::DateTime::parse_from_str(value, fmt).ok())
+        });
+
+    if let Some(starts_at) = starts_at {
+        return Ok(starts_at.timestamp());
+    }
+
+    if ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .any(|fmt| chrono::NaiveDateTime::parse_from_str(value, fmt).is_ok())
+    {
+        anyhow::bail!(invalid_field("starts_at", "date has no time zone offset"));
+    }
+
+    anyhow::bail!(invalid_field(
+        "starts_at",
+        format!("invalid date '{value}'")
+    ))
+}
+
+pub async fn schedule_price<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: SchedulePriceInput,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let price = Money::parse(&input.amount, input.currency)?;
+    if price.amount < 0 {
+        anyhow::bail!(invalid_field("amount", "price cannot be negative"));
+    }
+
+    let starts_at = parse_starts_at(&input.starts_at)?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    if starts_at <= now {
+        anyhow::bail!(invalid_field(
+            "starts_at",
+            "scheduled price must start in the future"
+        ));
+    }
+
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if product.item.state != super::ProductState::Ready {
+        anyhow::bail!(Conflict::new("product not ready"));
+    }
+
+    let variant_id = input.variant_id.filter(|id| !id.is_empty());
+    if let Some(variant_id) = &variant_id
+        && !product
+            .item
+            .variants
+            .iter()
+            .any(|variant| &variant.id == variant_id)
+    {
+        anyhow::bail!(NotFound::new("variant not found"));
+    }
+
+    Ok(evento::save_with(product).data(&PriceScheduled {
+        schedule: ScheduledPrice {
+            id: ulid::Ulid::new().to_string(),
+            variant_id,
+            price,
+            starts_at,
+        },
+    })?)
+}
+
+#[derive(Deserialize)]
+pub struct UnschedulePriceInput {
+    pub schedule_id: String,
+}
+
+pub async fn unschedule_price<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    input: UnschedulePriceInput,
+) -> anyhow::Result<evento::SaveBuilder<Product>> {
+    let product = evento::load::<Product, _>(executor, id).await?;
+    if !product
+        .item
+        .scheduled_prices
+        .iter()
+        .any(|schedule| schedule.id == input.schedule_id)
+    {
+        anyhow::bail!(NotFound::new("scheduled price not found"));
+    }
+
+    Ok(evento::save_with(product).data(&PriceUnscheduled {
+        schedule_id: input.schedule_id,
+    })?)
+}
+
+pub async fn apply_scheduled_price<E: evento::Executor>(
+    executor: &E,
+    id: impl Into<String>,
+    schedule_id: impl Into<String>,
+) -> anyhow::Result<Option<evento::SaveBuilder<Product>>> {
+    let schedule_id = schedule_id.into();
+    let product = evento::load::<Product, _>(executor, id).await?;
+    let Some(schedule) = product
+        .item
+        .scheduled_prices
+        .iter()
+        .find(|schedule| schedule.id == schedule_id)
+        .cloned()
+    else {
+        return Ok(None);
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    if schedule.starts_at > now {
+        return Ok(None);
+    }
+
+    let variant_exists = match &schedule.variant_id {
+        Some(variant_id) => product
+            .item
+            .variants
+            .iter()
+            .any(|variant| &variant.id == variant_id),
+        _ => true,
+    };
+
+    if product.item.state == super::ProductState::Deleted || !variant_exists {
+        return Ok(Some(
+            evento::save_with(product).data(&PriceUnscheduled { schedule_id })?,
+        ));
+    }
+
+    Ok(Some(evento::save_with(product).data(&PriceChanged {
+        variant_id: schedule.variant_id,
+        price: schedule.price,
+        schedule_id: Some(schedule_id),
+    })?))
+}
+
+/// Product holding a name, names are unique by region and owner.
+async fn find_product_name_id(
+    pool: &QueryPool,
+    routing_key: String,
+    owner_id: String,
+    name: String,
+) -> anyhow::Result<Option<String>> {
+    let statement = Query::select()
+        .column(CommandProductNameIden::Id)
+        .from(CommandProductNameIden::Table)
+        .and_where(Expr::col(CommandProductNameIden::RoutingKey).eq(routing_key))
+        .and_where(Expr::col(CommandProductNameIden::OwnerId).eq(owner_id))
+        .and_where(Expr::col(CommandProductNameIden::Name).eq(name))
+        .order_by(CommandProductNameIden::Id, Order::Asc)
+        .limit(1)
+        .to_owned();
+
+    Ok(pool
+        .fetch_optional::<(String,), _>(&statement)
+        .await?
+        .map(|(id,)| id))
+}
+
+/// Routing key and owner of a product, its name is reserved under them whoever renames it.
+async fn product_name_namespace<E: evento::Executor>(
+    executor: &E,
+    id: &str,
+) -> anyhow::Result<(String, String)> {
+    let product = evento::load::<Product, _>(executor, id).await?;
+
+    Ok((
+        product.event.routing_key.unwrap_or_default(),
+        product.item.owner_id.unwrap_or_default(),
+    ))
+}
+
 #[evento::handler(Product)]
 async fn command_create_requested<E: evento::Executor>(
     context: &evento::Context<'_, E>,
     event: RequestEvent<CreateRequested>,
 ) -> anyhow::Result<()> {
-    evento::save::<Product>(&event.aggregator_id)
-        .data(&Created {
+    let pool = context.extract::<QueryPool>();
+    let (routing_key, owner_id) =
+        product_name_namespace(context.executor, &event.aggregator_id).await?;
+    let name = event.data.name.trim().to_lowercase();
+
+    let statement = Query::insert()
+        .into_table(CommandProductNameIden::Table)
+        .columns([
+            CommandProductNameIden::Id,
+            CommandProductNameIden::RoutingKey,
+            CommandProductNameIden::OwnerId,
+            CommandProductNameIden::Name,
+        ])
+        .values_panic([
+            event.aggregator_id.to_owned().into(),
+            routing_key.to_owned().into(),
+            owner_id.to_owned().into(),
+            name.to_owned().into(),
+        ])
+        .on_conflict(
+            OnConflict::columns([
+                CommandProductNameIden::RoutingKey,
+                CommandProductNameIden::OwnerId,
+                CommandProductNameIden::Name,
+            ])
+            .do_nothing_on([CommandProductNameIden::Id])
+            .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    let id = find_product_name_id(&pool, routing_key, owner_id, name).await?;
+
+    let builder = if id.as_ref() == Some(&event.aggregator_id) {
+        evento::save::<Product>(&event.aggregator_id).data(&Created {
             state: super::ProductState::Ready,
         })?
+    } else {
+        // Only a redelivered event can find its own row under another name.
+        let statement = Query::delete()
+            .from_table(CommandProductNameIden::Table)
+            .and_where(Expr::col(CommandProductNameIden::Id).eq(event.aggregator_id.to_owned()))
+            .to_owned();
+
+        pool.execute(&statement).await?;
+
+        evento::save::<Product>(&event.aggregator_id).data(&CreateFailed {
+            state: super::ProductState::Failed,
+            failed_reason: "product name already exists".to_owned(),
+        })?
+    };
+
+    builder
         .metadata(&event.metadata)?
         .commit(context.executor)
         .await?;
@@ -38,6 +595,169 @@ async fn command_create_requested<E: evento::Executor>(
     Ok(())
 }
 
+#[evento::handler(Product)]
+async fn command_updated<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Updated>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let (routing_key, owner_id) =
+        product_name_namespace(context.executor, &event.aggregator_id).await?;
+    let name = event.data.name.trim().to_lowercase();
+
+    let id = find_product_name_id(
+        &pool,
+        routing_key.to_owned(),
+        owner_id.to_owned(),
+        name.to_owned(),
+    )
+    .await?;
+
+    // `update` checked the name, another product took it before this event was handled. The
+    // product keeps its previous name reserved rather than none.
+    if let Some(id) = id.filter(|id| id != &event.aggregator_id) {
+        tracing::warn!(
+            "product '{}' renamed to '{name}' already taken by '{id}'",
+            event.aggregator_id
+        );
+
+        return Ok(());
+    }
+
+    let statement = Query::delete()
+        .from_table(CommandProductNameIden::Table)
+        .and_where(Expr::col(CommandProductNameIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    let statement = Query::insert()
+        .into_table(CommandProductNameIden::Table)
+        .columns([
+            CommandProductNameIden::Id,
+            CommandProductNameIden::RoutingKey,
+            CommandProductNameIden::OwnerId,
+            CommandProductNameIden::Name,
+        ])
+        .values_panic([
+            event.aggregator_id.to_owned().into(),
+            routing_key.into(),
+            owner_id.into(),
+            name.into(),
+        ])
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn command_deleted<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Deleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(CommandProductNameIden::Table)
+        .and_where(Expr::col(CommandProductNameIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    let statement = Query::delete()
+        .from_table(CommandVariantSkuIden::Table)
+        .and_where(Expr::col(CommandVariantSkuIden::ProductId).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+/// Variant holding a sku, whichever product it belongs to.
+async fn find_variant_sku_id(pool: &QueryPool, sku: String) -> anyhow::Result<Option<String>> {
+    let statement = Query::select()
+        .column(CommandVariantSkuIden::VariantId)
+        .from(CommandVariantSkuIden::Table)
+        .and_where(Expr::col(CommandVariantSkuIden::Sku).eq(sku))
+        .to_owned();
+
+    Ok(pool
+        .fetch_optional::<(String,), _>(&statement)
+        .await?
+        .map(|(id,)| id))
+}
+
+#[evento::handler(Product)]
+async fn command_variant_added<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<VariantAdded>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let variant = &event.data.variant;
+    let statement = Query::insert()
+        .into_table(CommandVariantSkuIden::Table)
+        .columns([
+            CommandVariantSkuIden::Sku,
+            CommandVariantSkuIden::ProductId,
+            CommandVariantSkuIden::VariantId,
+        ])
+        .values_panic([
+            variant.sku.to_owned().into(),
+            event.aggregator_id.to_owned().into(),
+            variant.id.to_owned().into(),
+        ])
+        .on_conflict(
+            OnConflict::column(CommandVariantSkuIden::Sku)
+                .do_nothing_on([CommandVariantSkuIden::Sku])
+                .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    let id = find_variant_sku_id(&pool, variant.sku.to_owned()).await?;
+    if id.as_ref() == Some(&variant.id) {
+        return Ok(());
+    }
+
+    // `add_variants` checked the sku, another product took it before this event was handled.
+    tracing::warn!(
+        "variant '{}' of product '{}' removed, sku '{}' already taken by '{}'",
+        variant.id,
+        event.aggregator_id,
+        variant.sku,
+        id.unwrap_or_default()
+    );
+
+    evento::save::<Product>(&event.aggregator_id)
+        .data(&VariantRemoved {
+            variant_id: variant.id.to_owned(),
+        })?
+        .metadata(&event.metadata)?
+        .commit(context.executor)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn command_variant_removed<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<VariantRemoved>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(CommandVariantSkuIden::Table)
+        .and_where(Expr::col(CommandVariantSkuIden::VariantId).eq(event.data.variant_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
 pub fn subscribe_command<E: evento::Executor + Clone>(
     region: impl Into<String>,
 ) -> SubscribeBuilder<E> {
@@ -48,5 +768,39 @@ pub fn subscribe_command<E: evento::Executor + Clone>(
         .aggregator::<Product>()
         .skip::<Product, CreateFailed>()
         .skip::<Product, Created>()
-        .handler(command_create_requested())
+        .skip::<Product, Archived>()
+        .skip::<Product, Restored>()
+        .skip::<Product, CategoryAssigned>()
+        .skip::<Product, CategoryUnassigned>()
+        .skip::<Product, PriceChanged>()
+        .skip::<Product, PriceScheduled>()
+        .skip::<Product, PriceUnscheduled>()
+        .handler(Reported(command_create_requested()))
+        .handler(Reported(command_updated()))
+        .handler(Reported(command_deleted()))
+        .handler(Reported(command_variant_added()))
+        .handler(Reported(command_variant_removed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_starts_at_applies_the_offset() {
+        let utc = parse_starts_at("2026-10-20T08:00:00.000Z").ok();
+
+        assert_eq!(utc, Some(1792483200));
+        assert_eq!(parse_starts_at("2026-10-20T10:00+02:00").ok(), utc);
+        assert_eq!(parse_starts_at("2026-10-20 03:00-05:00").ok(), utc);
+    }
+
+    #[test]
+    fn parse_starts_at_requires_an_offset() {
+        let err = parse_starts_at("2026-10-20T10:00").unwrap_err();
+        let errors = err.downcast_ref::<validator::ValidationErrors>();
+
+        assert!(errors.is_some_and(|errors| errors.field_errors().contains_key("starts_at")));
+        assert!(parse_starts_at("tomorrow").is_err());
+    }
 }
@@ -3,21 +3,35 @@ mod query;
 
 use evento::AggregatorName;
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumString};
+use timada_shared::Money;
+use utoipa::ToSchema;
 
 pub use command::*;
 pub use query::*;
 
-use crate::RequestEvent;
+use crate::{RequestEvent, price::ScheduledPrice, variant::Variant};
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, sqlx::Type, Display)]
-#[sqlx(type_name = "product_state", rename_all = "kebab-case")]
+#[derive(
+    Debug, Default, Serialize, Deserialize, Clone, PartialEq, Display, EnumString, ToSchema,
+)]
 #[strum(serialize_all = "kebab_case")]
 pub enum ProductState {
     #[default]
     Checking,
     Failed,
     Ready,
+    Archived,
+    Deleted,
+}
+
+/// Stored as text in the query store, a Postgres enum type would not match the column.
+impl TryFrom<String> for ProductState {
+    type Error = strum::ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
@@ -25,6 +39,11 @@ pub struct Product {
     pub name: String,
     pub state: ProductState,
     pub failed_reason: String,
+    pub category_ids: Vec<String>,
+    pub variants: Vec<Variant>,
+    pub price: Option<Money>,
+    pub scheduled_prices: Vec<ScheduledPrice>,
+    pub owner_id: Option<String>,
 }
 
 #[evento::aggregator]
@@ -35,12 +54,14 @@ impl Product {
     ) -> anyhow::Result<()> {
         self.name = event.data.name;
         self.state = event.data.state;
+        self.owner_id = event.metadata.user_owner_id;
 
         Ok(())
     }
 
     async fn create_failed(&mut self, event: RequestEvent<CreateFailed>) -> anyhow::Result<()> {
         self.state = event.data.state;
+        self.failed_reason = event.data.failed_reason;
 
         Ok(())
     }
@@ -50,6 +71,96 @@ impl Product {
 
         Ok(())
     }
+
+    async fn updated(&mut self, event: RequestEvent<Updated>) -> anyhow::Result<()> {
+        self.name = event.data.name;
+
+        Ok(())
+    }
+
+    async fn archived(&mut self, event: RequestEvent<Archived>) -> anyhow::Result<()> {
+        self.state = event.data.state;
+
+        Ok(())
+    }
+
+    async fn restored(&mut self, event: RequestEvent<Restored>) -> anyhow::Result<()> {
+        self.state = event.data.state;
+
+        Ok(())
+    }
+
+    async fn deleted(&mut self, event: RequestEvent<Deleted>) -> anyhow::Result<()> {
+        self.state = event.data.state;
+
+        Ok(())
+    }
+
+    async fn category_assigned(
+        &mut self,
+        event: RequestEvent<CategoryAssigned>,
+    ) -> anyhow::Result<()> {
+        self.category_ids.push(event.data.category_id);
+
+        Ok(())
+    }
+
+    async fn category_unassigned(
+        &mut self,
+        event: RequestEvent<CategoryUnassigned>,
+    ) -> anyhow::Result<()> {
+        self.category_ids.retain(|id| id != &event.data.category_id);
+
+        Ok(())
+    }
+
+    async fn variant_added(&mut self, event: RequestEvent<VariantAdded>) -> anyhow::Result<()> {
+        self.variants.push(event.data.variant);
+
+        Ok(())
+    }
+
+    async fn variant_removed(&mut self, event: RequestEvent<VariantRemoved>) -> anyhow::Result<()> {
+        self.variants
+            .retain(|variant| variant.id != event.data.variant_id);
+
+        Ok(())
+    }
+
+    async fn price_changed(&mut self, event: RequestEvent<PriceChanged>) -> anyhow::Result<()> {
+        if let Some(schedule_id) = &event.data.schedule_id {
+            self.scheduled_prices
+                .retain(|schedule| &schedule.id != schedule_id);
+        }
+
+        let Some(variant_id) = event.data.variant_id else {
+            self.price = Some(event.data.price);
+
+            return Ok(());
+        };
+
+        if let Some(variant) = self.variants.iter_mut().find(|v| v.id == variant_id) {
+            variant.price = Some(event.data.price);
+        }
+
+        Ok(())
+    }
+
+    async fn price_scheduled(&mut self, event: RequestEvent<PriceScheduled>) -> anyhow::Result<()> {
+        self.scheduled_prices.push(event.data.schedule);
+
+        Ok(())
+    }
+
+    async fn price_unscheduled(
+        &mut self,
+        event: RequestEvent<PriceUnscheduled>,
+    ) -> anyhow::Result<()> {
+        self.scheduled_prices
+            .retain(|schedule| schedule.id != event.data.schedule_id);
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
@@ -68,3 +179,61 @@ pub struct CreateFailed {
     pub state: ProductState,
     pub failed_reason: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Updated {
+    pub name: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Archived {
+    pub state: ProductState,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Restored {
+    pub state: ProductState,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct Deleted {
+    pub state: ProductState,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct CategoryAssigned {
+    pub category_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct CategoryUnassigned {
+    pub category_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, AggregatorName)]
+pub struct VariantAdded {
+    pub variant: Variant,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct VariantRemoved {
+    pub variant_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct PriceChanged {
+    pub variant_id: Option<String>,
+    pub price: Money,
+    #[serde(default)]
+    pub schedule_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct PriceScheduled {
+    pub schedule: ScheduledPrice,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, AggregatorName)]
+pub struct PriceUnscheduled {
+    pub schedule_id: String,
+}
@@ -1,23 +1,58 @@
 use crate::{
     RequestEvent,
-    product::{CreateFailed, CreateRequested, Created, Product, ProductState},
+    product::{
+        Archived, CategoryAssigned, CategoryUnassigned, CreateFailed, CreateRequested, Created,
+        Deleted, PriceChanged, PriceScheduled, PriceUnscheduled, Product, ProductState, Restored,
+        Updated, VariantAdded, VariantRemoved,
+    },
 };
+use chrono::NaiveDate;
 use evento::{AggregatorName, SubscribeBuilder, sql::Reader};
-use sea_query::{Expr, ExprTrait, Query, SqliteQueryBuilder};
-use sea_query_sqlx::SqlxBinder;
-use serde::{Deserialize, Serialize};
-use sqlx::{SqlitePool, prelude::FromRow};
+use sea_query::{Expr, ExprTrait, Func, IntoTableRef, LikeExpr, OnConflict, Query};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sqlx::prelude::FromRow;
+use std::marker::PhantomData;
+use strum::{Display, EnumString};
+use timada_shared::{Backend, Money, QueryPool, Reported, format_created_at};
+use utoipa::ToSchema;
 
-#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow, ToSchema)]
 #[sea_query::enum_def]
 pub struct QueryProduct {
     pub id: String,
     pub name: String,
     pub failed_reason: String,
+    #[sqlx(try_from = "String")]
     pub state: ProductState,
+    pub price_amount: Option<i64>,
+    pub price_currency: Option<String>,
+    pub owner_id: Option<String>,
     pub created_at: String,
 }
 
+impl QueryProduct {
+    pub fn price(&self) -> Option<Money> {
+        Some(Money {
+            amount: self.price_amount?,
+            currency: self.price_currency.to_owned()?,
+        })
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryProductCategory {
+    pub product_id: String,
+    pub category_id: String,
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryProductFts {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryProductCursor {
     pub i: String,
@@ -57,24 +92,37 @@ async fn products_create_requested<E: evento::Executor>(
     context: &evento::Context<'_, E>,
     event: RequestEvent<CreateRequested>,
 ) -> anyhow::Result<()> {
-    let pool = context.extract::<SqlitePool>();
-    let mut conn = pool.acquire().await?;
+    let pool = context.extract::<QueryPool>();
     let statement = Query::insert()
         .into_table(QueryProductIden::Table)
         .columns([
             QueryProductIden::Id,
             QueryProductIden::Name,
             QueryProductIden::State,
+            QueryProductIden::OwnerId,
+            QueryProductIden::CreatedAt,
         ])
         .values_panic([
             event.aggregator_id.to_owned().into(),
-            event.data.name.into(),
+            event.data.name.to_owned().into(),
             event.data.state.to_string().into(),
+            event.metadata.user_owner_id.to_owned().into(),
+            format_created_at(event.timestamp).into(),
+        ])
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    let statement = Query::insert()
+        .into_table(QueryProductFtsIden::Table)
+        .columns([QueryProductFtsIden::Id, QueryProductFtsIden::Name])
+        .values_panic([
+            event.aggregator_id.to_owned().into(),
+            event.data.name.into(),
         ])
         .to_owned();
 
-    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
-    sqlx::query_with(&sql, values).execute(&mut *conn).await?;
+    pool.execute(&statement).await?;
 
     Ok(())
 }
@@ -84,8 +132,7 @@ async fn products_create_failed<E: evento::Executor>(
     context: &evento::Context<'_, E>,
     event: RequestEvent<CreateFailed>,
 ) -> anyhow::Result<()> {
-    let pool = context.extract::<SqlitePool>();
-    let mut conn = pool.acquire().await?;
+    let pool = context.extract::<QueryPool>();
     let statement = Query::update()
         .table(QueryProductIden::Table)
         .values([
@@ -98,8 +145,7 @@ async fn products_create_failed<E: evento::Executor>(
         .and_where(Expr::col(QueryProductIden::Id).eq(event.aggregator_id.to_owned()))
         .to_owned();
 
-    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
-    sqlx::query_with(&sql, values).execute(&mut *conn).await?;
+    pool.execute(&statement).await?;
 
     Ok(())
 }
@@ -109,37 +155,533 @@ async fn products_created<E: evento::Executor>(
     context: &evento::Context<'_, E>,
     event: RequestEvent<Created>,
 ) -> anyhow::Result<()> {
-    let pool = context.extract::<SqlitePool>();
-    let mut conn = pool.acquire().await?;
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryProductIden::Table)
+        .values([(QueryProductIden::State, event.data.state.to_string().into())])
+        .and_where(Expr::col(QueryProductIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn products_updated<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Updated>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryProductIden::Table)
+        .values([(QueryProductIden::Name, event.data.name.to_owned().into())])
+        .and_where(Expr::col(QueryProductIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    let statement = Query::update()
+        .table(QueryProductFtsIden::Table)
+        .values([(QueryProductFtsIden::Name, event.data.name.to_owned().into())])
+        .and_where(Expr::col(QueryProductFtsIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn products_archived<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Archived>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
     let statement = Query::update()
         .table(QueryProductIden::Table)
         .values([(QueryProductIden::State, event.data.state.to_string().into())])
         .and_where(Expr::col(QueryProductIden::Id).eq(event.aggregator_id.to_owned()))
         .to_owned();
 
-    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
-    sqlx::query_with(&sql, values).execute(&mut *conn).await?;
+    pool.execute(&statement).await?;
 
     Ok(())
 }
 
+#[evento::handler(Product)]
+async fn products_restored<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Restored>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryProductIden::Table)
+        .values([(QueryProductIden::State, event.data.state.to_string().into())])
+        .and_where(Expr::col(QueryProductIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn products_deleted<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Deleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryProductIden::Table)
+        .values([(QueryProductIden::State, event.data.state.to_string().into())])
+        .and_where(Expr::col(QueryProductIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    let statement = Query::delete()
+        .from_table(QueryProductFtsIden::Table)
+        .and_where(Expr::col(QueryProductFtsIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn products_price_changed<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<PriceChanged>,
+) -> anyhow::Result<()> {
+    if event.data.variant_id.is_some() {
+        return Ok(());
+    }
+
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryProductIden::Table)
+        .values([
+            (
+                QueryProductIden::PriceAmount,
+                event.data.price.amount.into(),
+            ),
+            (
+                QueryProductIden::PriceCurrency,
+                event.data.price.currency.to_owned().into(),
+            ),
+        ])
+        .and_where(Expr::col(QueryProductIden::Id).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn products_category_assigned<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<CategoryAssigned>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::insert()
+        .into_table(QueryProductCategoryIden::Table)
+        .columns([
+            QueryProductCategoryIden::ProductId,
+            QueryProductCategoryIden::CategoryId,
+        ])
+        .values_panic([
+            event.aggregator_id.to_owned().into(),
+            event.data.category_id.to_owned().into(),
+        ])
+        .on_conflict(
+            OnConflict::columns([
+                QueryProductCategoryIden::ProductId,
+                QueryProductCategoryIden::CategoryId,
+            ])
+            .do_nothing_on([QueryProductCategoryIden::ProductId])
+            .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn products_category_unassigned<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<CategoryUnassigned>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(QueryProductCategoryIden::Table)
+        .and_where(
+            Expr::col(QueryProductCategoryIden::ProductId).eq(event.aggregator_id.to_owned()),
+        )
+        .and_where(
+            Expr::col(QueryProductCategoryIden::CategoryId).eq(event.data.category_id.to_owned()),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[derive(Default, Deserialize)]
+pub struct QueryProductsFilter {
+    pub state: Option<ProductState>,
+    #[serde(default)]
+    pub include_archived: bool,
+    pub created_from: Option<NaiveDate>,
+    pub created_to: Option<NaiveDate>,
+    pub name_prefix: Option<String>,
+    pub category_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Display, EnumString)]
+#[strum(serialize_all = "kebab_case")]
+pub enum QueryProductsSortKey {
+    #[default]
+    CreatedAt,
+    Name,
+    /// Products without a price are left out when sorting by price.
+    Price,
+}
+
+#[derive(Default, Deserialize, Clone, Copy)]
+pub struct QueryProductsSort {
+    #[serde(default)]
+    pub key: QueryProductsSortKey,
+    #[serde(default)]
+    pub desc: bool,
+}
+
+#[derive(Default, Deserialize)]
+pub struct QueryProductsInput {
+    #[serde(default)]
+    pub filter: QueryProductsFilter,
+    pub search: Option<String>,
+    #[serde(default)]
+    pub sort: QueryProductsSort,
+    #[serde(default)]
+    pub args: evento::cursor::Args,
+}
+
+/// Turns user input into a full text condition where every word is a prefix, `red mu` gives
+/// `"red"* "mu"*` with sqlite FTS5, `+red* +mu*` with MySQL and `red:* & mu:*` with Postgres.
+fn search_match(backend: Backend, search: &str) -> Option<Expr> {
+    let words = search.split_whitespace();
+    let (condition, terms) = match backend {
+        Backend::Sqlite => (
+            r#""query_product_fts" MATCH ?"#,
+            words
+                .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Backend::MySql => (
+            "MATCH (`name`) AGAINST (? IN BOOLEAN MODE)",
+            search_words(words)
+                .map(|word| format!("+{word}*"))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Backend::Postgres => (
+            r#"to_tsvector('simple', "name") @@ to_tsquery('simple', $1)"#,
+            search_words(words)
+                .map(|word| format!("{word}:*"))
+                .collect::<Vec<_>>()
+                .join(" & "),
+        ),
+    };
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(Expr::cust_with_values(condition, [terms]))
+}
+
+/// MySQL and Postgres have their own operators in full text queries, only letters and digits
+/// are kept from each word.
+fn search_words<'a>(words: impl Iterator<Item = &'a str>) -> impl Iterator<Item = String> {
+    words
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+}
+
+pub trait QueryProductSortKey: Send + Unpin {
+    type Value: Serialize + DeserializeOwned + Into<Expr>;
+
+    fn column() -> QueryProductIden;
+    fn value(product: &QueryProduct) -> Self::Value;
+}
+
+pub struct SortByCreatedAt;
+
+impl QueryProductSortKey for SortByCreatedAt {
+    type Value = String;
+
+    fn column() -> QueryProductIden {
+        QueryProductIden::CreatedAt
+    }
+
+    fn value(product: &QueryProduct) -> Self::Value {
+        product.created_at.to_owned()
+    }
+}
+
+pub struct SortByName;
+
+impl QueryProductSortKey for SortByName {
+    type Value = String;
+
+    fn column() -> QueryProductIden {
+        QueryProductIden::Name
+    }
+
+    fn value(product: &QueryProduct) -> Self::Value {
+        product.name.to_owned()
+    }
+}
+
+pub struct SortByPrice;
+
+impl QueryProductSortKey for SortByPrice {
+    type Value = i64;
+
+    fn column() -> QueryProductIden {
+        QueryProductIden::PriceAmount
+    }
+
+    fn value(product: &QueryProduct) -> Self::Value {
+        product.price_amount.unwrap_or_default()
+    }
+}
+
+/// A `QueryProduct` read with `(K, id)` as sort key, `Bind::columns` being static this is how
+/// the reader gets a different key per query.
+pub struct SortedQueryProduct<K> {
+    pub product: QueryProduct,
+    key: PhantomData<K>,
+}
+
+impl<'r, R: sqlx::Row, K> FromRow<'r, R> for SortedQueryProduct<K>
+where
+    QueryProduct: FromRow<'r, R>,
+{
+    fn from_row(row: &'r R) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            product: QueryProduct::from_row(row)?,
+            key: PhantomData,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SortedQueryProductCursor<V> {
+    pub i: String,
+    pub k: V,
+}
+
+impl<K: QueryProductSortKey> evento::cursor::Cursor for SortedQueryProduct<K> {
+    type T = SortedQueryProductCursor<K::Value>;
+
+    fn serialize(&self) -> Self::T {
+        Self::T {
+            i: self.product.id.to_owned(),
+            k: K::value(&self.product),
+        }
+    }
+}
+
+impl<K: QueryProductSortKey> evento::sql::Bind for SortedQueryProduct<K> {
+    type T = QueryProductIden;
+    type I = [Self::T; 2];
+    type V = [Expr; 2];
+    type Cursor = Self;
+
+    fn columns() -> Self::I {
+        [K::column(), QueryProductIden::Id]
+    }
+
+    fn values(
+        cursor: <<Self as evento::sql::Bind>::Cursor as evento::cursor::Cursor>::T,
+    ) -> Self::V {
+        [cursor.k.into(), cursor.i.into()]
+    }
+}
+
+async fn read_sorted<K: QueryProductSortKey + 'static>(
+    reader: &mut Reader,
+    pool: &QueryPool,
+) -> anyhow::Result<evento::cursor::ReadResult<QueryProduct>> {
+    let result = pool.read::<SortedQueryProduct<K>>(reader).await?;
+
+    Ok(evento::cursor::ReadResult {
+        edges: result
+            .edges
+            .into_iter()
+            .map(|edge| evento::cursor::Edge {
+                cursor: edge.cursor,
+                node: edge.node.product,
+            })
+            .collect(),
+        page_info: result.page_info,
+    })
+}
+
+/// Only products of `owner_id` are read, products without owner when it is `None`.
 pub async fn query_products(
-    pool: &SqlitePool,
+    pool: &QueryPool,
+    owner_id: Option<&str>,
+    input: QueryProductsInput,
 ) -> anyhow::Result<evento::cursor::ReadResult<QueryProduct>> {
-    let mut conn = pool.acquire().await?;
+    let filter = input.filter;
+    let search = input
+        .search
+        .as_deref()
+        .and_then(|search| search_match(pool.backend(), search));
+    let name_prefix = filter
+        .name_prefix
+        .as_deref()
+        .map(str::trim)
+        .filter(|prefix| !prefix.is_empty())
+        .map(|prefix| {
+            let prefix = prefix
+                .to_lowercase()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+
+            LikeExpr::new(format!("{prefix}%")).escape('\\')
+        });
+    let category_id = filter.category_id.filter(|id| !id.is_empty());
 
-    let statement = Query::select()
+    let mut statement = Query::select()
         .columns([
             QueryProductIden::Id,
             QueryProductIden::Name,
             QueryProductIden::State,
             QueryProductIden::CreatedAt,
             QueryProductIden::FailedReason,
+            QueryProductIden::PriceAmount,
+            QueryProductIden::PriceCurrency,
+            QueryProductIden::OwnerId,
         ])
         .from(QueryProductIden::Table)
+        .and_where(match owner_id {
+            Some(owner_id) => Expr::col(QueryProductIden::OwnerId).eq(owner_id),
+            _ => Expr::col(QueryProductIden::OwnerId).is_null(),
+        })
+        .conditions(
+            filter.state.is_some(),
+            |q| {
+                if let Some(state) = &filter.state {
+                    q.and_where(Expr::col(QueryProductIden::State).eq(state.to_string()));
+                }
+            },
+            |q| {
+                if !filter.include_archived {
+                    q.and_where(Expr::col(QueryProductIden::State).is_not_in([
+                        ProductState::Archived.to_string(),
+                        ProductState::Deleted.to_string(),
+                    ]));
+                }
+            },
+        )
+        .conditions(
+            search.is_some(),
+            |q| {
+                if let Some(search) = search {
+                    q.and_where(
+                        Expr::col(QueryProductIden::Id).in_subquery(
+                            Query::select()
+                                .column(QueryProductFtsIden::Id)
+                                .from(QueryProductFtsIden::Table)
+                                .and_where(search)
+                                .to_owned(),
+                        ),
+                    );
+                }
+            },
+            |_| {},
+        )
         .to_owned();
 
-    Ok(Reader::new(statement).execute(&mut *conn).await?)
+    if let Some(created_from) = filter.created_from {
+        statement.and_where(
+            Expr::col(QueryProductIden::CreatedAt)
+                .gte(created_from.format("%Y-%m-%d 00:00:00").to_string()),
+        );
+    }
+
+    if let Some(created_to) = filter.created_to.and_then(|date| date.succ_opt()) {
+        statement.and_where(
+            Expr::col(QueryProductIden::CreatedAt)
+                .lt(created_to.format("%Y-%m-%d 00:00:00").to_string()),
+        );
+    }
+
+    if let Some(name_prefix) = name_prefix {
+        statement.and_where(Func::lower(Expr::col(QueryProductIden::Name)).like(name_prefix));
+    }
+
+    if let Some(category_id) = category_id {
+        statement.and_where(
+            Expr::col(QueryProductIden::Id).in_subquery(
+                Query::select()
+                    .column(QueryProductCategoryIden::ProductId)
+                    .from(QueryProductCategoryIden::Table)
+                    .and_where(Expr::col(QueryProductCategoryIden::CategoryId).eq(category_id))
+                    .to_owned(),
+            ),
+        );
+    }
+
+    if input.sort.key == QueryProductsSortKey::Price {
+        statement.and_where(Expr::col(QueryProductIden::PriceAmount).is_not_null());
+    }
+
+    let mut args = input.args;
+    args.first = args.first.map(|first| first.min(100));
+    args.last = args.last.map(|last| last.min(100));
+
+    let mut reader = Reader::new(statement);
+    reader.args(args);
+
+    if input.sort.desc {
+        reader.desc();
+    }
+
+    match input.sort.key {
+        QueryProductsSortKey::CreatedAt => read_sorted::<SortByCreatedAt>(&mut reader, pool).await,
+        QueryProductsSortKey::Name => read_sorted::<SortByName>(&mut reader, pool).await,
+        QueryProductsSortKey::Price => read_sorted::<SortByPrice>(&mut reader, pool).await,
+    }
+}
+
+/// The search index is emptied with the products, their names are indexed again on replay.
+pub async fn reset_query_products(pool: &QueryPool) -> anyhow::Result<()> {
+    for table in [
+        QueryProductIden::Table.into_table_ref(),
+        QueryProductCategoryIden::Table.into_table_ref(),
+        QueryProductFtsIden::Table.into_table_ref(),
+    ] {
+        let statement = Query::delete().from_table(table).to_owned();
+        pool.execute(&statement).await?;
+    }
+
+    Ok(())
 }
 
 pub fn subscribe_query_products<E: evento::Executor + Clone>(
@@ -151,8 +693,19 @@ pub fn subscribe_query_products<E: evento::Executor + Clone>(
         evento::subscribe(format!("market.{region}.product.query.products"))
             .routing_key(region)
             .aggregator::<Product>()
-            .handler(products_create_requested())
-            .handler(products_created())
-            .handler(products_create_failed()),
+            .skip::<Product, VariantAdded>()
+            .skip::<Product, VariantRemoved>()
+            .skip::<Product, PriceScheduled>()
+            .skip::<Product, PriceUnscheduled>()
+            .handler(Reported(products_create_requested()))
+            .handler(Reported(products_created()))
+            .handler(Reported(products_create_failed()))
+            .handler(Reported(products_updated()))
+            .handler(Reported(products_archived()))
+            .handler(Reported(products_restored()))
+            .handler(Reported(products_deleted()))
+            .handler(Reported(products_category_assigned()))
+            .handler(Reported(products_category_unassigned()))
+            .handler(Reported(products_price_changed())),
     )
 }
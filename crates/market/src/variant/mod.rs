@@ -0,0 +1,253 @@
+mod query;
+
+use serde::{Deserialize, Serialize};
+use timada_shared::{Money, invalid_field};
+use utoipa::ToSchema;
+
+pub use query::*;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProductOption {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct VariantOption {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Variant {
+    pub id: String,
+    pub sku: String,
+    pub options: Vec<VariantOption>,
+    #[serde(default)]
+    pub price: Option<Money>,
+}
+
+impl Variant {
+    pub fn label(&self) -> String {
+        self.options
+            .iter()
+            .map(|option| option.value.to_owned())
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+pub fn parse_options(value: &str) -> anyhow::Result<Vec<ProductOption>> {
+    let mut options: Vec<ProductOption> = vec![];
+
+    for line in value.split(['\n', ';']) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some((name, values)) = line.split_once(':') else {
+            anyhow::bail!(invalid_field(
+                "options",
+                "option must be formatted as 'name: value, value'"
+            ));
+        };
+
+        let name = name.trim().to_owned();
+        if name.is_empty() {
+            anyhow::bail!(invalid_field("options", "option name cannot be empty"));
+        }
+
+        if options
+            .iter()
+            .any(|option| option.name.eq_ignore_ascii_case(&name))
+        {
+            anyhow::bail!(invalid_field(
+                "options",
+                format!("option '{name}' defined twice")
+            ));
+        }
+
+        let mut option = ProductOption {
+            name,
+            values: vec![],
+        };
+
+        for value in values.split(',') {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+
+            if code(value, usize::MAX).is_empty() {
+                anyhow::bail!(invalid_field(
+                    "options",
+                    format!("value '{value}' must contain letters or digits")
+                ));
+            }
+
+            if option
+                .values
+                .iter()
+                .any(|v| code(v, usize::MAX) == code(value, usize::MAX))
+            {
+                anyhow::bail!(invalid_field(
+                    "options",
+                    format!("value '{value}' defined twice in option '{}'", option.name)
+                ));
+            }
+
+            option.values.push(value.to_owned());
+        }
+
+        if option.values.is_empty() {
+            anyhow::bail!(invalid_field(
+                "options",
+                format!("option '{}' has no value", option.name)
+            ));
+        }
+
+        options.push(option);
+    }
+
+    if options.is_empty() {
+        anyhow::bail!(invalid_field("options", "at least one option is required"));
+    }
+
+    if options.len() > 3 {
+        anyhow::bail!(invalid_field(
+            "options",
+            "a product cannot have more than 3 options"
+        ));
+    }
+
+    Ok(options)
+}
+
+pub fn generate(options: &[ProductOption]) -> Vec<Vec<VariantOption>> {
+    options.iter().fold(vec![vec![]], |combinations, option| {
+        combinations
+            .into_iter()
+            .flat_map(|combination| {
+                option.values.iter().map(move |value| {
+                    let mut combination = combination.clone();
+                    combination.push(VariantOption {
+                        name: option.name.to_owned(),
+                        value: value.to_owned(),
+                    });
+                    combination
+                })
+            })
+            .collect()
+    })
+}
+
+pub fn sku(product_id: &str, product_name: &str, options: &[VariantOption]) -> String {
+    let suffix_start = product_id.len().saturating_sub(8);
+    let mut parts = vec![
+        code(product_name, 6),
+        product_id[suffix_start..].to_uppercase(),
+    ];
+
+    for option in options {
+        parts.push(value_code(&option.value));
+    }
+
+    parts.join("-")
+}
+
+/// Code of an option value, at most 10 characters. Longer values end with a hash of their full
+/// code, values sharing their first characters keep different codes.
+fn value_code(value: &str) -> String {
+    let code = code(value, usize::MAX);
+    if code.len() <= 10 {
+        return code;
+    }
+
+    // FNV-1a, the hash of a value must not change between releases.
+    let mut hash = code.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+
+    let mut suffix = String::new();
+    for _ in 0..4 {
+        let digit = char::from_digit(hash % 36, 36).unwrap_or('0');
+        suffix.insert(0, digit.to_ascii_uppercase());
+        hash /= 36;
+    }
+
+    format!("{}{suffix}", &code[..6])
+}
+
+fn code(value: &str, len: usize) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .take(len)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(values: &[&str]) -> Vec<VariantOption> {
+        values
+            .iter()
+            .map(|value| VariantOption {
+                name: "size".to_owned(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sku_keeps_short_values() {
+        assert_eq!(
+            sku("01K7S3Z5X9ABCDEFGH", "T-shirt", &options(&["XL", "Red"])),
+            "TSHIRT-ABCDEFGH-XL-RED"
+        );
+    }
+
+    #[test]
+    fn sku_does_not_collide_on_truncated_values() {
+        let long = sku(
+            "01K7S3Z5X9ABCDEFGH",
+            "Shirt",
+            &options(&["Extra Large Long"]),
+        );
+        let short = sku(
+            "01K7S3Z5X9ABCDEFGH",
+            "Shirt",
+            &options(&["Extra Large Short"]),
+        );
+
+        assert_ne!(long, short);
+        assert!(long.starts_with("SHIRT-ABCDEFGH-EXTRAL"));
+        assert_eq!(long.rsplit('-').next().map(str::len), Some(10));
+    }
+
+    #[test]
+    fn sku_is_stable() {
+        assert_eq!(
+            sku(
+                "01K7S3Z5X9ABCDEFGH",
+                "Shirt",
+                &options(&["Extra Large Long"])
+            ),
+            "SHIRT-ABCDEFGH-EXTRALKMBQ"
+        );
+    }
+
+    #[test]
+    fn parse_options_rejects_duplicate_values() {
+        assert!(parse_options("size: XL, x-l").is_err());
+        assert_eq!(
+            parse_options("size: S, M; color: red")
+                .map(|options| generate(&options).len())
+                .ok(),
+            Some(2)
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    RequestEvent,
+    product::{
+        Archived, CategoryAssigned, CategoryUnassigned, CreateFailed, CreateRequested, Created,
+        Deleted, PriceChanged, PriceScheduled, PriceUnscheduled, Product, Restored, Updated,
+        VariantAdded, VariantRemoved,
+    },
+};
+use evento::{AggregatorName, SubscribeBuilder, sql::Reader};
+use sea_query::{Expr, ExprTrait, OnConflict, Query};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use timada_shared::{Money, QueryPool, Reported, format_created_at};
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct QueryVariant {
+    pub id: String,
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    pub price_amount: Option<i64>,
+    pub price_currency: Option<String>,
+    pub created_at: String,
+}
+
+impl QueryVariant {
+    pub fn price(&self) -> Option<Money> {
+        Some(Money {
+            amount: self.price_amount?,
+            currency: self.price_currency.to_owned()?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryVariantCursor {
+    pub i: String,
+    pub c: String,
+}
+
+impl evento::cursor::Cursor for QueryVariant {
+    type T = QueryVariantCursor;
+
+    fn serialize(&self) -> Self::T {
+        Self::T {
+            i: self.id.to_owned(),
+            c: self.created_at.to_owned(),
+        }
+    }
+}
+
+impl evento::sql::Bind for QueryVariant {
+    type T = QueryVariantIden;
+    type I = [Self::T; 2];
+    type V = [Expr; 2];
+    type Cursor = Self;
+
+    fn columns() -> Self::I {
+        [QueryVariantIden::CreatedAt, QueryVariantIden::Id]
+    }
+
+    fn values(
+        cursor: <<Self as evento::sql::Bind>::Cursor as evento::cursor::Cursor>::T,
+    ) -> Self::V {
+        [cursor.c.into(), cursor.i.into()]
+    }
+}
+
+#[evento::handler(Product)]
+async fn variants_variant_added<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<VariantAdded>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::insert()
+        .into_table(QueryVariantIden::Table)
+        .columns([
+            QueryVariantIden::Id,
+            QueryVariantIden::ProductId,
+            QueryVariantIden::Sku,
+            QueryVariantIden::Name,
+            QueryVariantIden::CreatedAt,
+        ])
+        .values_panic([
+            event.data.variant.id.to_owned().into(),
+            event.aggregator_id.to_owned().into(),
+            event.data.variant.sku.to_owned().into(),
+            event.data.variant.label().into(),
+            format_created_at(event.timestamp).into(),
+        ])
+        // The product command subscription removes a variant added with a taken sku.
+        .on_conflict(
+            OnConflict::column(QueryVariantIden::Sku)
+                .do_nothing_on([QueryVariantIden::Sku])
+                .to_owned(),
+        )
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn variants_variant_removed<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<VariantRemoved>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(QueryVariantIden::Table)
+        .and_where(Expr::col(QueryVariantIden::Id).eq(event.data.variant_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn variants_price_changed<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<PriceChanged>,
+) -> anyhow::Result<()> {
+    let Some(variant_id) = &event.data.variant_id else {
+        return Ok(());
+    };
+
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::update()
+        .table(QueryVariantIden::Table)
+        .values([
+            (
+                QueryVariantIden::PriceAmount,
+                event.data.price.amount.into(),
+            ),
+            (
+                QueryVariantIden::PriceCurrency,
+                event.data.price.currency.to_owned().into(),
+            ),
+        ])
+        .and_where(Expr::col(QueryVariantIden::Id).eq(variant_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[evento::handler(Product)]
+async fn variants_deleted<E: evento::Executor>(
+    context: &evento::Context<'_, E>,
+    event: RequestEvent<Deleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<QueryPool>();
+    let statement = Query::delete()
+        .from_table(QueryVariantIden::Table)
+        .and_where(Expr::col(QueryVariantIden::ProductId).eq(event.aggregator_id.to_owned()))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub async fn query_variants(
+    pool: &QueryPool,
+    product_id: impl Into<String>,
+) -> anyhow::Result<evento::cursor::ReadResult<QueryVariant>> {
+    let statement = Query::select()
+        .columns([
+            QueryVariantIden::Id,
+            QueryVariantIden::ProductId,
+            QueryVariantIden::Sku,
+            QueryVariantIden::Name,
+            QueryVariantIden::PriceAmount,
+            QueryVariantIden::PriceCurrency,
+            QueryVariantIden::CreatedAt,
+        ])
+        .from(QueryVariantIden::Table)
+        .and_where(Expr::col(QueryVariantIden::ProductId).eq(product_id.into()))
+        .to_owned();
+
+    pool.read(Reader::new(statement).forward(1000, None)).await
+}
+
+pub async fn reset_query_variants(pool: &QueryPool) -> anyhow::Result<()> {
+    let statement = Query::delete()
+        .from_table(QueryVariantIden::Table)
+        .to_owned();
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub fn subscribe_query_variants<E: evento::Executor + Clone>(
+    region: impl Into<String>,
+) -> anyhow::Result<SubscribeBuilder<E>> {
+    let region = region.into();
+
+    Ok(
+        evento::subscribe(format!("market.{region}.product.query.variants"))
+            .routing_key(region)
+            .aggregator::<Product>()
+            .skip::<Product, CreateRequested>()
+            .skip::<Product, CreateFailed>()
+            .skip::<Product, Created>()
+            .skip::<Product, Updated>()
+            .skip::<Product, Archived>()
+            .skip::<Product, Restored>()
+            .skip::<Product, CategoryAssigned>()
+            .skip::<Product, CategoryUnassigned>()
+            .skip::<Product, PriceScheduled>()
+            .skip::<Product, PriceUnscheduled>()
+            .handler(Reported(variants_variant_added()))
+            .handler(Reported(variants_variant_removed()))
+            .handler(Reported(variants_deleted()))
+            .handler(Reported(variants_price_changed())),
+    )
+}
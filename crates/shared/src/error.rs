@@ -0,0 +1,51 @@
+use std::fmt;
+use validator::{ValidationError, ValidationErrors};
+
+/// Aggregate or row that does not exist, or is not visible to the requesting user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotFound(pub String);
+
+impl NotFound {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for NotFound {}
+
+/// Command rejected because of the current state of an aggregate, e.g. a duplicate email or a
+/// product that is not ready.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict(pub String);
+
+impl Conflict {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Conflict {}
+
+/// Input rejected by a command after its `Validate` checks, e.g. a negative price. Reported like
+/// the derived errors, as a message of the field.
+pub fn invalid_field(field: &'static str, message: impl Into<String>) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    errors.add(
+        field,
+        ValidationError::new("invalid").with_message(message.into().into()),
+    );
+
+    errors
+}
@@ -1,5 +1,17 @@
+mod error;
+mod migrator;
+mod money;
+mod query;
+mod subscribe;
+
 use serde::{Deserialize, Serialize};
 
+pub use error::*;
+pub use migrator::*;
+pub use money::*;
+pub use query::*;
+pub use subscribe::*;
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct RequestMetadata {
     pub id: String,
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+
+use sqlx::{Any, AnyConnection};
+use sqlx_migrator::{
+    Info, Migrate, Migration, Migrator, Operation, migration::AppliedMigrationSqlRow,
+    migrator::DatabaseOperation,
+};
+
+use crate::Backend;
+
+/// Operation of a query store migration, the statements it runs on a backend.
+///
+/// They are built without a connection so `timada migrate --dry-run` prints them without
+/// running them.
+pub trait Statements: Send + Sync {
+    fn up(&self, backend: Backend) -> Vec<String>;
+
+    fn down(&self, backend: Backend) -> Vec<String>;
+}
+
+/// Runs the statements of an operation on the connection of the migration.
+pub struct StatementsOperation(pub Box<dyn Statements>);
+
+#[async_trait::async_trait]
+impl Operation<Any> for StatementsOperation {
+    async fn up(&self, connection: &mut AnyConnection) -> Result<(), sqlx_migrator::Error> {
+        for statement in self.0.up(Backend::of(connection)?) {
+            sqlx::query(&statement).execute(&mut *connection).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, connection: &mut AnyConnection) -> Result<(), sqlx_migrator::Error> {
+        for statement in self.0.down(Backend::of(connection)?) {
+            sqlx::query(&statement).execute(&mut *connection).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Query store migration, its operations are [`Statements`].
+pub trait QueryMigration: Migration<Any> {
+    fn statements(&self) -> Vec<Box<dyn Statements>>;
+}
+
+/// Same as `sqlx_migrator::any_migration!` with operations implementing [`Statements`].
+#[macro_export]
+macro_rules! query_migration {
+    ($op:ty, $app_name:literal, $migration_name:literal, $parents:expr, $operations:expr) => {
+        impl sqlx_migrator::migration::Migration<sqlx::Any> for $op {
+            fn app(&self) -> &str {
+                $app_name
+            }
+
+            fn name(&self) -> &str {
+                $migration_name
+            }
+
+            fn parents(&self) -> Vec<Box<dyn sqlx_migrator::migration::Migration<sqlx::Any>>> {
+                $parents
+            }
+
+            fn operations(&self) -> Vec<Box<dyn sqlx_migrator::operation::Operation<sqlx::Any>>> {
+                $crate::QueryMigration::statements(self)
+                    .into_iter()
+                    .map(|statements| {
+                        Box::new($crate::StatementsOperation(statements))
+                            as Box<dyn sqlx_migrator::operation::Operation<sqlx::Any>>
+                    })
+                    .collect()
+            }
+        }
+
+        impl $crate::QueryMigration for $op {
+            fn statements(&self) -> Vec<Box<dyn $crate::Statements>> {
+                $operations
+            }
+        }
+    };
+}
+
+/// Runs the query store migrations on any backend.
+///
+/// `Migrator<sqlx::Any>` reads `CURRENT_DATABASE()` to lock a Postgres database and the sqlite
+/// `applied_time` column as is, the Any driver can decode neither (`name` and `DATETIME` types).
+/// Both are read as text here, every other operation is left to the inner migrator.
+#[derive(Default)]
+pub struct QueryMigrator {
+    migrator: Migrator<Any>,
+    statements: HashMap<(String, String), Vec<Box<dyn Statements>>>,
+}
+
+impl QueryMigrator {
+    pub fn add_migration(
+        &mut self,
+        migration: impl QueryMigration + 'static,
+    ) -> Result<(), sqlx_migrator::Error> {
+        self.statements.insert(
+            (migration.app().to_owned(), migration.name().to_owned()),
+            migration.statements(),
+        );
+
+        self.migrator.add_migration(Box::new(migration))
+    }
+
+    /// Statements of a migration in the order they run, reversed when it is reverted.
+    pub fn statements(
+        &self,
+        migration: &dyn Migration<Any>,
+        backend: Backend,
+        revert: bool,
+    ) -> Vec<String> {
+        let Some(operations) = self
+            .statements
+            .get(&(migration.app().to_owned(), migration.name().to_owned()))
+        else {
+            return vec![];
+        };
+
+        if revert {
+            return operations
+                .iter()
+                .rev()
+                .flat_map(|operation| operation.down(backend))
+                .collect();
+        }
+
+        operations
+            .iter()
+            .flat_map(|operation| operation.up(backend))
+            .collect()
+    }
+
+    async fn postgres_lock_id(
+        &self,
+        connection: &mut AnyConnection,
+    ) -> Result<i64, sqlx_migrator::Error> {
+        let (database_name,) = sqlx::query_as::<_, (String,)>("SELECT CURRENT_DATABASE()::TEXT")
+            .fetch_one(connection)
+            .await?;
+
+        let buf = format!("{database_name}/{}", self.migrator.table_name());
+
+        Ok(i64::from(crc32fast::hash(buf.as_bytes())))
+    }
+}
+
+impl Info<Any> for QueryMigrator {
+    fn migrations(&self) -> &Vec<Box<dyn Migration<Any>>> {
+        self.migrator.migrations()
+    }
+
+    fn migrations_mut(&mut self) -> &mut Vec<Box<dyn Migration<Any>>> {
+        self.migrator.migrations_mut()
+    }
+}
+
+#[async_trait::async_trait]
+impl DatabaseOperation<Any> for QueryMigrator {
+    async fn ensure_migration_table_exists(
+        &self,
+        connection: &mut AnyConnection,
+    ) -> Result<(), sqlx_migrator::Error> {
+        self.migrator
+            .ensure_migration_table_exists(connection)
+            .await
+    }
+
+    async fn drop_migration_table_if_exists(
+        &self,
+        connection: &mut AnyConnection,
+    ) -> Result<(), sqlx_migrator::Error> {
+        self.migrator
+            .drop_migration_table_if_exists(connection)
+            .await
+    }
+
+    async fn add_migration_to_db_table(
+        &self,
+        connection: &mut AnyConnection,
+        migration: &Box<dyn Migration<Any>>,
+    ) -> Result<(), sqlx_migrator::Error> {
+        self.migrator
+            .add_migration_to_db_table(connection, migration)
+            .await
+    }
+
+    async fn delete_migration_from_db_table(
+        &self,
+        connection: &mut AnyConnection,
+        migration: &Box<dyn Migration<Any>>,
+    ) -> Result<(), sqlx_migrator::Error> {
+        self.migrator
+            .delete_migration_from_db_table(connection, migration)
+            .await
+    }
+
+    async fn fetch_applied_migration_from_db(
+        &self,
+        connection: &mut AnyConnection,
+    ) -> Result<Vec<AppliedMigrationSqlRow>, sqlx_migrator::Error> {
+        if Backend::of(connection)? != Backend::Sqlite {
+            return self
+                .migrator
+                .fetch_applied_migration_from_db(connection)
+                .await;
+        }
+
+        let statement = format!(
+            "SELECT id, app, name, CAST(applied_time AS TEXT) AS applied_time FROM {}",
+            self.migrator.table_name()
+        );
+
+        Ok(sqlx::query_as::<_, AppliedMigrationSqlRow>(&statement)
+            .fetch_all(connection)
+            .await?)
+    }
+
+    async fn lock(&self, connection: &mut AnyConnection) -> Result<(), sqlx_migrator::Error> {
+        if Backend::of(connection)? != Backend::Postgres {
+            return self.migrator.lock(connection).await;
+        }
+
+        let lock_id = self.postgres_lock_id(connection).await?;
+        sqlx::query("SELECT pg_advisory_lock($1)")
+            .bind(lock_id)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn unlock(&self, connection: &mut AnyConnection) -> Result<(), sqlx_migrator::Error> {
+        if Backend::of(connection)? != Backend::Postgres {
+            return self.migrator.unlock(connection).await;
+        }
+
+        let lock_id = self.postgres_lock_id(connection).await?;
+        sqlx::query("SELECT pg_advisory_unlock($1)")
+            .bind(lock_id)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl Migrate<Any> for QueryMigrator {}
+
+/// Turns the `created_at` column of a table, created as a timestamp defaulting to
+/// `CURRENT_TIMESTAMP`, into the `varchar(19)` written by `format_created_at`. Sqlite can not
+/// drop an indexed column, indexes on `created_at` have to be dropped before.
+pub struct CreatedAtToStringOperation(pub &'static str);
+
+impl Statements for CreatedAtToStringOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        let table = self.0;
+        match backend {
+            Backend::Sqlite => vec![
+                format!(
+                    r#"ALTER TABLE "{table}" ADD COLUMN "created_at_string" varchar(19) NOT NULL DEFAULT ''"#
+                ),
+                format!(r#"UPDATE "{table}" SET "created_at_string" = "created_at""#),
+                format!(r#"ALTER TABLE "{table}" DROP COLUMN "created_at""#),
+                format!(
+                    r#"ALTER TABLE "{table}" RENAME COLUMN "created_at_string" TO "created_at""#
+                ),
+            ],
+            Backend::MySql => vec![format!(
+                "ALTER TABLE `{table}` MODIFY `created_at` varchar(19) NOT NULL"
+            )],
+            Backend::Postgres => vec![
+                format!(r#"ALTER TABLE "{table}" ALTER COLUMN "created_at" DROP DEFAULT"#),
+                format!(
+                    r#"ALTER TABLE "{table}" ALTER COLUMN "created_at" TYPE varchar(19) USING to_char("created_at" AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')"#
+                ),
+            ],
+        }
+    }
+
+    /// Sqlite keeps an empty default, it can not add a column defaulting to `CURRENT_TIMESTAMP`.
+    fn down(&self, backend: Backend) -> Vec<String> {
+        let table = self.0;
+        match backend {
+            Backend::Sqlite => vec![
+                format!(
+                    r#"ALTER TABLE "{table}" ADD COLUMN "created_at_timestamp" timestamp_with_timezone_text NOT NULL DEFAULT ''"#
+                ),
+                format!(r#"UPDATE "{table}" SET "created_at_timestamp" = "created_at""#),
+                format!(r#"ALTER TABLE "{table}" DROP COLUMN "created_at""#),
+                format!(
+                    r#"ALTER TABLE "{table}" RENAME COLUMN "created_at_timestamp" TO "created_at""#
+                ),
+            ],
+            Backend::MySql => vec![format!(
+                "ALTER TABLE `{table}` MODIFY `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP"
+            )],
+            Backend::Postgres => vec![
+                format!(
+                    r#"ALTER TABLE "{table}" ALTER COLUMN "created_at" TYPE timestamp with time zone USING ("created_at" || '+00')::timestamptz"#
+                ),
+                format!(
+                    r#"ALTER TABLE "{table}" ALTER COLUMN "created_at" SET DEFAULT CURRENT_TIMESTAMP"#
+                ),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TableOperation(&'static str);
+
+    impl Statements for TableOperation {
+        fn up(&self, _backend: Backend) -> Vec<String> {
+            vec![format!("CREATE TABLE {}", self.0)]
+        }
+
+        fn down(&self, _backend: Backend) -> Vec<String> {
+            vec![format!("DROP TABLE {}", self.0)]
+        }
+    }
+
+    struct First;
+
+    crate::query_migration!(
+        First,
+        "main",
+        "first",
+        vec![],
+        sqlx_migrator::vec_box![TableOperation("a"), TableOperation("b")]
+    );
+
+    struct Second;
+
+    crate::query_migration!(
+        Second,
+        "main",
+        "second",
+        sqlx_migrator::vec_box![First],
+        sqlx_migrator::vec_box![TableOperation("c")]
+    );
+
+    #[test]
+    fn statements_are_reversed_on_revert() {
+        let mut migrator = QueryMigrator::default();
+        assert!(migrator.add_migration(First).is_ok());
+
+        assert_eq!(
+            migrator.statements(&First, Backend::Sqlite, false),
+            ["CREATE TABLE a", "CREATE TABLE b"]
+        );
+        assert_eq!(
+            migrator.statements(&First, Backend::Sqlite, true),
+            ["DROP TABLE b", "DROP TABLE a"]
+        );
+    }
+
+    #[test]
+    fn statements_of_an_unknown_migration_are_empty() {
+        let mut migrator = QueryMigrator::default();
+        assert!(migrator.add_migration(First).is_ok());
+
+        assert!(
+            migrator
+                .statements(&Second, Backend::Postgres, false)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn created_at_to_string_depends_on_the_backend() {
+        let operation = CreatedAtToStringOperation("query_product");
+
+        assert_eq!(
+            operation.up(Backend::MySql),
+            ["ALTER TABLE `query_product` MODIFY `created_at` varchar(19) NOT NULL"]
+        );
+        assert_eq!(operation.up(Backend::Sqlite).len(), 4);
+        assert_eq!(operation.down(Backend::Postgres).len(), 2);
+    }
+}
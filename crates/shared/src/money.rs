@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
+    InvalidCurrency(String),
+    InvalidAmount(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCurrency(currency) => write!(f, "invalid currency '{currency}'"),
+            Self::InvalidAmount(amount) => write!(f, "invalid amount '{amount}'"),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+/// An amount in the smallest unit of an ISO 4217 currency, `1250 EUR` is 12.50€.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Money {
+    pub amount: i64,
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(amount: i64, currency: impl Into<String>) -> Result<Self, MoneyError> {
+        let currency = currency.into().to_uppercase();
+
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(MoneyError::InvalidCurrency(currency));
+        }
+
+        Ok(Self { amount, currency })
+    }
+
+    /// Parse a decimal amount such as `12.5` or `12,50` in the given currency.
+    pub fn parse(value: &str, currency: impl Into<String>) -> Result<Self, MoneyError> {
+        let money = Self::new(0, currency)?;
+        let exponent = money.exponent();
+        let value = value.trim().replace(',', ".");
+        let invalid = || MoneyError::InvalidAmount(value.to_owned());
+
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            _ => (false, value.as_str()),
+        };
+
+        let (units, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if units.is_empty()
+            || fraction.len() > exponent as usize
+            || !units.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let fraction = format!("{fraction:0<width$}", width = exponent as usize);
+        let amount = format!("{units}{fraction}")
+            .parse::<i64>()
+            .map_err(|_| invalid())?;
+
+        Ok(Self {
+            amount: if negative { -amount } else { amount },
+            ..money
+        })
+    }
+
+    /// Amount as a decimal string without the currency, `1250 EUR` gives `12.50`.
+    pub fn decimal(&self) -> String {
+        let exponent = self.exponent();
+        let sign = if self.amount < 0 { "-" } else { "" };
+        let amount = self.amount.unsigned_abs();
+
+        if exponent == 0 {
+            return format!("{sign}{amount}");
+        }
+
+        let factor = 10u64.pow(exponent);
+        format!(
+            "{sign}{}.{:0width$}",
+            amount / factor,
+            amount % factor,
+            width = exponent as usize
+        )
+    }
+
+    /// Number of decimal digits of the currency minor unit.
+    pub fn exponent(&self) -> u32 {
+        match self.currency.as_str() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.decimal(), self.currency)
+    }
+}
+
+/// Default currency used to price products of a region.
+pub fn region_currency(region: &str) -> &'static str {
+    match region {
+        "eu-west-2" => "GBP",
+        "eu-north-1" => "SEK",
+        "eu-central-2" => "CHF",
+        "ca-central-1" | "ca-west-1" => "CAD",
+        "sa-east-1" => "BRL",
+        "ap-northeast-1" | "ap-northeast-3" => "JPY",
+        "ap-northeast-2" => "KRW",
+        "ap-south-1" | "ap-south-2" => "INR",
+        "ap-southeast-1" => "SGD",
+        "ap-southeast-2" | "ap-southeast-4" => "AUD",
+        "ap-east-1" => "HKD",
+        "af-south-1" => "ZAR",
+        "me-central-1" => "AED",
+        "il-central-1" => "ILS",
+        region if region.starts_with("eu-") => "EUR",
+        _ => "USD",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_uses_the_currency_exponent() {
+        assert_eq!(Money::parse("12.5", "eur"), Money::new(1250, "EUR"));
+        assert_eq!(Money::parse(" 12,50 ", "EUR"), Money::new(1250, "EUR"));
+        assert_eq!(Money::parse("1000", "JPY"), Money::new(1000, "JPY"));
+        assert_eq!(Money::parse("1.234", "KWD"), Money::new(1234, "KWD"));
+        assert_eq!(Money::parse("-3.2", "USD"), Money::new(-320, "USD"));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_amounts() {
+        assert_eq!(
+            Money::parse("1.999", "EUR"),
+            Err(MoneyError::InvalidAmount("1.999".to_owned()))
+        );
+        assert!(Money::parse("10.5", "JPY").is_err());
+        assert!(Money::parse(".5", "EUR").is_err());
+        assert!(Money::parse("1e3", "EUR").is_err());
+        assert_eq!(
+            Money::parse("1", "eu"),
+            Err(MoneyError::InvalidCurrency("EU".to_owned()))
+        );
+    }
+
+    #[test]
+    fn display_formats_the_minor_unit() {
+        let money = |amount, currency| Money::new(amount, currency).map(|money| money.to_string());
+
+        assert_eq!(money(1250, "EUR"), Ok("12.50 EUR".to_owned()));
+        assert_eq!(money(-5, "EUR"), Ok("-0.05 EUR".to_owned()));
+        assert_eq!(money(1000, "JPY"), Ok("1000 JPY".to_owned()));
+        assert_eq!(money(1234, "KWD"), Ok("1.234 KWD".to_owned()));
+    }
+
+    #[test]
+    fn region_currency_falls_back_to_usd() {
+        assert_eq!(region_currency("eu-west-3"), "EUR");
+        assert_eq!(region_currency("eu-west-2"), "GBP");
+        assert_eq!(region_currency("us-east-1"), "USD");
+    }
+}
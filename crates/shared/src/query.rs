@@ -0,0 +1,271 @@
+use evento::{
+    cursor::{Cursor, ReadResult},
+    sql::{Bind, Reader},
+};
+use sea_query::{MysqlQueryBuilder, PostgresQueryBuilder, SchemaBuilder, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::{
+    AnyConnection, Executor, FromRow, MySql, MySqlPool, PgPool, Postgres, Sqlite, SqlitePool,
+    mysql::{MySqlPoolOptions, MySqlRow},
+    pool::PoolOptions,
+    postgres::{PgConnectOptions, PgPoolOptions, PgRow},
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow},
+};
+use std::{str::FromStr, time::Duration};
+
+/// Backend of the query store, projections are written with sea-query so the same statement
+/// runs on each of them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    Sqlite,
+    MySql,
+    Postgres,
+}
+
+impl Backend {
+    /// Backend of a migration connection, from `AnyConnection::backend_name`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            <Sqlite as sqlx::Database>::NAME => Some(Self::Sqlite),
+            <MySql as sqlx::Database>::NAME => Some(Self::MySql),
+            <Postgres as sqlx::Database>::NAME => Some(Self::Postgres),
+            _ => None,
+        }
+    }
+
+    /// Backend of the connection a migration runs on.
+    pub fn of(connection: &AnyConnection) -> Result<Self, sqlx_migrator::Error> {
+        Self::from_name(connection.backend_name()).ok_or(sqlx_migrator::Error::UnsupportedDatabase)
+    }
+
+    pub fn schema_builder(&self) -> &'static dyn SchemaBuilder {
+        match self {
+            Self::Sqlite => &SqliteQueryBuilder,
+            Self::MySql => &MysqlQueryBuilder,
+            Self::Postgres => &PostgresQueryBuilder,
+        }
+    }
+}
+
+/// Event timestamp as stored in `created_at` columns, the format sqlite gives to
+/// `CURRENT_TIMESTAMP` so values compare as strings on every backend.
+pub fn format_created_at(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// A row that can be read from every backend of the query store.
+pub trait QueryRow:
+    for<'r> FromRow<'r, SqliteRow>
+    + for<'r> FromRow<'r, MySqlRow>
+    + for<'r> FromRow<'r, PgRow>
+    + Send
+    + Unpin
+{
+}
+
+impl<T> QueryRow for T where
+    T: for<'r> FromRow<'r, SqliteRow>
+        + for<'r> FromRow<'r, MySqlRow>
+        + for<'r> FromRow<'r, PgRow>
+        + Send
+        + Unpin
+{
+}
+
+/// Settings of a query store pool, unset values keep sqlx defaults.
+#[derive(Debug, Default, Clone)]
+pub struct QueryPoolOptions {
+    pub max_connections: Option<u32>,
+    /// How long a sqlite connection waits for a lock before failing with `database is locked`.
+    pub busy_timeout: Option<Duration>,
+    /// Opens sqlite with `mode=ro` and Postgres/MySQL sessions as read only.
+    pub read_only: bool,
+}
+
+impl QueryPoolOptions {
+    fn pool<DB: sqlx::Database>(&self) -> PoolOptions<DB> {
+        match self.max_connections {
+            Some(max_connections) => PoolOptions::new().max_connections(max_connections),
+            _ => PoolOptions::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum QueryPool {
+    Sqlite(SqlitePool),
+    MySql(MySqlPool),
+    Postgres(PgPool),
+}
+
+impl QueryPool {
+    pub async fn connect(dsn: &str) -> anyhow::Result<Self> {
+        if dsn.starts_with("sqlite:") {
+            Ok(Self::Sqlite(SqlitePool::connect(dsn).await?))
+        } else if dsn.starts_with("mysql:") {
+            Ok(Self::MySql(MySqlPool::connect(dsn).await?))
+        } else if dsn.starts_with("postgres:") {
+            Ok(Self::Postgres(PgPool::connect(dsn).await?))
+        } else {
+            anyhow::bail!("{dsn} not supported, consider using Sqlite, MySql or Postgres")
+        }
+    }
+
+    /// Writers put sqlite in WAL mode so readers opened with `read_only` are never blocked by
+    /// projections.
+    pub async fn connect_with(dsn: &str, options: &QueryPoolOptions) -> anyhow::Result<Self> {
+        if dsn.starts_with("sqlite:") {
+            let mut connect_options = SqliteConnectOptions::from_str(dsn)?;
+
+            connect_options = if options.read_only {
+                connect_options.read_only(true)
+            } else {
+                connect_options.journal_mode(SqliteJournalMode::Wal)
+            };
+
+            if let Some(busy_timeout) = options.busy_timeout {
+                connect_options = connect_options.busy_timeout(busy_timeout);
+            }
+
+            let pool: SqlitePoolOptions = options.pool();
+
+            Ok(Self::Sqlite(pool.connect_with(connect_options).await?))
+        } else if dsn.starts_with("mysql:") {
+            let mut pool: MySqlPoolOptions = options.pool();
+
+            if options.read_only {
+                pool = pool.after_connect(|conn, _| {
+                    Box::pin(async move {
+                        conn.execute("SET SESSION TRANSACTION READ ONLY").await?;
+
+                        Ok(())
+                    })
+                });
+            }
+
+            Ok(Self::MySql(pool.connect(dsn).await?))
+        } else if dsn.starts_with("postgres:") {
+            let mut connect_options = PgConnectOptions::from_str(dsn)?;
+
+            if options.read_only {
+                connect_options =
+                    connect_options.options([("default_transaction_read_only", "on")]);
+            }
+
+            let pool: PgPoolOptions = options.pool();
+
+            Ok(Self::Postgres(pool.connect_with(connect_options).await?))
+        } else {
+            anyhow::bail!("{dsn} not supported, consider using Sqlite, MySql or Postgres")
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self {
+            Self::Sqlite(_) => Backend::Sqlite,
+            Self::MySql(_) => Backend::MySql,
+            Self::Postgres(_) => Backend::Postgres,
+        }
+    }
+
+    pub async fn execute<S: SqlxBinder>(&self, statement: &S) -> anyhow::Result<u64> {
+        let result = match self {
+            Self::Sqlite(pool) => {
+                let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+                sqlx::query_with(&sql, values)
+                    .execute(pool)
+                    .await?
+                    .rows_affected()
+            }
+            Self::MySql(pool) => {
+                let (sql, values) = statement.build_sqlx(MysqlQueryBuilder);
+                sqlx::query_with(&sql, values)
+                    .execute(pool)
+                    .await?
+                    .rows_affected()
+            }
+            Self::Postgres(pool) => {
+                let (sql, values) = statement.build_sqlx(PostgresQueryBuilder);
+                sqlx::query_with(&sql, values)
+                    .execute(pool)
+                    .await?
+                    .rows_affected()
+            }
+        };
+
+        Ok(result)
+    }
+
+    pub async fn fetch_all<T: QueryRow, S: SqlxBinder>(
+        &self,
+        statement: &S,
+    ) -> anyhow::Result<Vec<T>> {
+        let rows = match self {
+            Self::Sqlite(pool) => {
+                let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+                sqlx::query_as_with::<_, T, _>(&sql, values)
+                    .fetch_all(pool)
+                    .await?
+            }
+            Self::MySql(pool) => {
+                let (sql, values) = statement.build_sqlx(MysqlQueryBuilder);
+                sqlx::query_as_with::<_, T, _>(&sql, values)
+                    .fetch_all(pool)
+                    .await?
+            }
+            Self::Postgres(pool) => {
+                let (sql, values) = statement.build_sqlx(PostgresQueryBuilder);
+                sqlx::query_as_with::<_, T, _>(&sql, values)
+                    .fetch_all(pool)
+                    .await?
+            }
+        };
+
+        Ok(rows)
+    }
+
+    pub async fn fetch_optional<T: QueryRow, S: SqlxBinder>(
+        &self,
+        statement: &S,
+    ) -> anyhow::Result<Option<T>> {
+        let row = match self {
+            Self::Sqlite(pool) => {
+                let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+                sqlx::query_as_with::<_, T, _>(&sql, values)
+                    .fetch_optional(pool)
+                    .await?
+            }
+            Self::MySql(pool) => {
+                let (sql, values) = statement.build_sqlx(MysqlQueryBuilder);
+                sqlx::query_as_with::<_, T, _>(&sql, values)
+                    .fetch_optional(pool)
+                    .await?
+            }
+            Self::Postgres(pool) => {
+                let (sql, values) = statement.build_sqlx(PostgresQueryBuilder);
+                sqlx::query_as_with::<_, T, _>(&sql, values)
+                    .fetch_optional(pool)
+                    .await?
+            }
+        };
+
+        Ok(row)
+    }
+
+    pub async fn read<O>(&self, reader: &mut Reader) -> anyhow::Result<ReadResult<O>>
+    where
+        O: QueryRow + Cursor + Bind<Cursor = O>,
+        <<O as Bind>::I as IntoIterator>::IntoIter: DoubleEndedIterator,
+        <<O as Bind>::V as IntoIterator>::IntoIter: DoubleEndedIterator,
+    {
+        let result = match self {
+            Self::Sqlite(pool) => reader.execute::<_, O, _>(pool).await?,
+            Self::MySql(pool) => reader.execute::<_, O, _>(pool).await?,
+            Self::Postgres(pool) => reader.execute::<_, O, _>(pool).await?,
+        };
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,47 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use evento::{Context, Event, Executor, SubscribeHandler};
+
+/// Receives the errors of the handlers of a subscription, evento only logs them before it
+/// retries the event. Every subscription running [`Reported`] handlers needs one in its data.
+#[derive(Clone)]
+pub struct HandlerErrors(Arc<ReportFn>);
+
+type ReportFn = dyn Fn(&Event, &anyhow::Error) + Send + Sync;
+
+impl HandlerErrors {
+    pub fn new(report: impl Fn(&Event, &anyhow::Error) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(report))
+    }
+}
+
+/// Handler passing its errors to the [`HandlerErrors`] of the subscription.
+pub struct Reported<H>(pub H);
+
+impl<E: Executor, H: SubscribeHandler<E>> SubscribeHandler<E> for Reported<H> {
+    fn handle<'async_trait>(
+        &'async_trait self,
+        context: &'async_trait Context<'_, E>,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'async_trait>>
+    where
+        Self: Sync + 'async_trait,
+    {
+        Box::pin(async move {
+            let result = self.0.handle(context).await;
+
+            if let Err(err) = &result {
+                (context.extract::<HandlerErrors>().0)(&context.event, err);
+            }
+
+            result
+        })
+    }
+
+    fn aggregator_type(&self) -> &'static str {
+        self.0.aggregator_type()
+    }
+
+    fn event_name(&self) -> &'static str {
+        self.0.event_name()
+    }
+}
@@ -0,0 +1,124 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use timada_identity::user::{Permission, QueryUser};
+
+use super::{Session, SessionRejection};
+use crate::filters;
+
+/// User of the `Authorization: Bearer` token or else of the session, read once per request and
+/// shared by the extractors that need it.
+pub struct CurrentUser {
+    pub user: QueryUser,
+    /// Authenticated by an API token instead of the session.
+    pub token: bool,
+}
+
+impl CurrentUser {
+    pub fn require(&self, permission: Permission) -> Result<(), Forbidden> {
+        if self.user.can(permission) {
+            return Ok(());
+        }
+
+        Err(Forbidden(permission.to_string()))
+    }
+
+    /// Same as `require` but refused to API tokens, a token must not mint or revoke tokens.
+    pub fn require_session(&self, permission: Permission) -> Result<(), Forbidden> {
+        if self.token {
+            return Err(Forbidden(permission.to_string()));
+        }
+
+        self.require(permission)
+    }
+}
+
+impl FromRequestParts<crate::State> for CurrentUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &crate::State,
+    ) -> Result<Self, Self::Rejection> {
+        let user = match parts.extensions.get::<QueryUser>() {
+            Some(user) => user.to_owned(),
+            _ => {
+                let user_id = match bearer(parts) {
+                    Some(secret) => {
+                        timada_identity::token::find_token_user_id(&state.query_pool, secret)
+                            .await
+                            .map_err(|err| crate::error::AppError::from(err).into_response())?
+                            .ok_or_else(|| unauthorized("invalid token"))?
+                    }
+                    // API clients get a 401 instead of the login page.
+                    _ if parts.uri.path().starts_with(crate::router::API) => {
+                        Session::from_request_parts(parts, state)
+                            .await
+                            .map_err(|_| unauthorized("missing token"))?
+                            .user_id
+                    }
+                    _ => {
+                        Session::from_request_parts(parts, state)
+                            .await
+                            .map_err(IntoResponse::into_response)?
+                            .user_id
+                    }
+                };
+
+                // Roles and owner are not part of the session, changes apply on the next request.
+                let user = timada_identity::user::find_user(&state.query_pool, &user_id)
+                    .await
+                    .map_err(|err| crate::error::AppError::from(err).into_response())?
+                    .ok_or_else(|| SessionRejection.into_response())?;
+
+                parts.extensions.insert(user.to_owned());
+
+                user
+            }
+        };
+
+        Ok(Self {
+            user,
+            token: bearer(parts).is_some(),
+        })
+    }
+}
+
+fn unauthorized(error: &str) -> Response {
+    let mut response = crate::error::error_response(StatusCode::UNAUTHORIZED, error);
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+
+    response
+}
+
+fn bearer(parts: &Parts) -> Option<&str> {
+    parts
+        .headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+#[derive(askama::Template, Clone)]
+#[template(path = "403.html")]
+pub struct ForbiddenTemplate {
+    pub permission: String,
+}
+
+/// Missing permission, `AppError` renders it as a 403.
+#[derive(Debug)]
+pub struct Forbidden(pub String);
+
+impl std::fmt::Display for Forbidden {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "forbidden '{}'", self.0)
+    }
+}
+
+impl std::error::Error for Forbidden {}
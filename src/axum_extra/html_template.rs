@@ -30,6 +30,7 @@ impl<S> tower_layer::Layer<S> for TemplateConfig {
     }
 }
 
+#[derive(Clone)]
 pub struct Template<T> {
     template: Option<T>,
     preferred_language: String,
@@ -1,19 +1,22 @@
-use axum::{extract::FromRequestParts, http::request::Parts};
-use std::convert::Infallible;
+use axum::{extract::FromRequestParts, http::request::Parts, response::Response};
 use timada_shared::RequestMetadata;
 use ulid::Ulid;
 
+use super::CurrentUser;
+
 impl FromRequestParts<crate::State> for RequestMetadata {
-    type Rejection = Infallible;
+    type Rejection = Response;
 
     async fn from_request_parts(
-        _req: &mut Parts,
-        _state: &crate::State,
+        parts: &mut Parts,
+        state: &crate::State,
     ) -> Result<Self, Self::Rejection> {
+        let CurrentUser { user, .. } = CurrentUser::from_request_parts(parts, state).await?;
+
         Ok(RequestMetadata {
             id: Ulid::new().to_string(),
-            user_id: "".to_owned(),
-            user_owner_id: None,
+            user_id: user.id,
+            user_owner_id: user.owner_id,
         })
     }
 }
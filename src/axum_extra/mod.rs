@@ -1,7 +1,11 @@
+mod current_user;
 mod html_template;
 mod language;
 mod metadata;
+mod session;
 
+pub use current_user::*;
 pub use html_template::*;
 pub use language::*;
 // pub use metadata::*;
+pub use session::*;
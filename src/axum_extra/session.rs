@@ -0,0 +1,79 @@
+use axum::{
+    extract::FromRequestParts,
+    http::request::Parts,
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    SignedCookieJar,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const COOKIE_NAME: &str = "timada_session";
+const MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// User logged in through the signed session cookie, requests without one are redirected to the
+/// login page.
+pub struct Session {
+    pub user_id: String,
+}
+
+impl Session {
+    /// The expiry is part of the signed value, the browser is not trusted to drop the cookie.
+    ///
+    /// Nothing is kept server side, logging out only removes the cookie from the browser and a
+    /// copy of it stays valid until it expires. Changing `session-secret` revokes every session.
+    pub fn cookie(user_id: &str, secure: bool) -> Cookie<'static> {
+        let expires_at = (SystemTime::now() + MAX_AGE)
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Cookie::build((COOKIE_NAME, format!("{user_id}:{expires_at}")))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .secure(secure)
+            .max_age(MAX_AGE.try_into().unwrap_or_default())
+            .build()
+    }
+
+    pub fn removal_cookie() -> Cookie<'static> {
+        Cookie::build(COOKIE_NAME).path("/").build()
+    }
+
+    fn from_jar(jar: &SignedCookieJar) -> Option<Self> {
+        let cookie = jar.get(COOKIE_NAME)?;
+        let (user_id, expires_at) = cookie.value().split_once(':')?;
+        let expires_at = UNIX_EPOCH + Duration::from_secs(expires_at.parse().ok()?);
+
+        if user_id.is_empty() || expires_at <= SystemTime::now() {
+            return None;
+        }
+
+        Some(Self {
+            user_id: user_id.to_owned(),
+        })
+    }
+}
+
+pub struct SessionRejection;
+
+impl IntoResponse for SessionRejection {
+    fn into_response(self) -> Response {
+        Redirect::to(crate::router::LOGIN).into_response()
+    }
+}
+
+impl FromRequestParts<crate::State> for Session {
+    type Rejection = SessionRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &crate::State,
+    ) -> Result<Self, Self::Rejection> {
+        let Ok(jar) = SignedCookieJar::from_request_parts(parts, state).await;
+
+        Self::from_jar(&jar).ok_or(SessionRejection)
+    }
+}
@@ -1,29 +1,332 @@
 use axum::{
-    http::StatusCode,
+    extract::{
+        rejection::{FormRejection, JsonRejection, PathRejection, QueryRejection},
+        Request,
+    },
+    http::{header, HeaderMap, StatusCode, Uri},
+    middleware::Next,
     response::{IntoResponse, Response},
+    Json,
 };
+use serde::Serialize;
+use std::collections::BTreeMap;
+use timada_shared::{Conflict, MoneyError, NotFound};
+use utoipa::ToSchema;
+use validator::ValidationErrors;
 
-// Make our own error that wraps `anyhow::Error`.
-pub struct AppError(anyhow::Error);
+use crate::{
+    axum_extra::{Forbidden, ForbiddenTemplate, Template},
+    filters,
+};
+
+/// Failure of a handler, rendered by `render` as an HTML page or as JSON for API requests.
+#[derive(Debug)]
+pub enum AppError {
+    /// Invalid input, with the messages of each field.
+    Validation {
+        message: String,
+        fields: BTreeMap<String, Vec<String>>,
+    },
+    NotFound(String),
+    /// Missing permission of the current user.
+    Forbidden(String),
+    Conflict(String),
+    /// Details are only logged, the response does not leak them.
+    Internal(anyhow::Error),
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Validation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn body(&self) -> ErrorBody {
+        let error = match self {
+            Self::Validation { message, .. } => message.to_owned(),
+            Self::NotFound(message) | Self::Conflict(message) => message.to_owned(),
+            Self::Forbidden(_) => "forbidden".to_owned(),
+            Self::Internal(_) => "Something went wrong".to_owned(),
+        };
+
+        let fields = match self {
+            Self::Validation { fields, .. } => fields.to_owned(),
+            _ => Default::default(),
+        };
+
+        let permission = match self {
+            Self::Forbidden(permission) => Some(permission.to_owned()),
+            _ => None,
+        };
+
+        ErrorBody {
+            error,
+            fields,
+            permission,
+        }
+    }
+}
+
+/// Body of every failed API response.
+#[derive(Serialize, Clone, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+    /// Messages by field of a 422.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Vec<String>>,
+    /// Permission missing for a 403.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission: Option<String>,
+}
+
+pub fn error_response(status: StatusCode, error: impl Into<String>) -> Response {
+    (
+        status,
+        Json(ErrorBody {
+            error: error.into(),
+            fields: Default::default(),
+            permission: None,
+        }),
+    )
+        .into_response()
+}
+
+/// Left on the response for `render`, which knows the language and kind of the request.
+#[derive(Clone)]
+struct ErrorExtension {
+    status: StatusCode,
+    body: ErrorBody,
+}
 
-// Tell axum how to convert `AppError` into a response.
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Something went wrong: {}", self.0),
-        )
-            .into_response()
+        if let Self::Internal(err) = &self {
+            tracing::error!("{err:?}");
+        }
+
+        let status = self.status();
+        let body = self.body();
+        let mut response = (status, body.error.to_owned()).into_response();
+        response
+            .extensions_mut()
+            .insert(ErrorExtension { status, body });
+
+        response
     }
 }
 
 // This enables using `?` on functions that return `Result<_, anyhow::Error>` to turn them into
-// `Result<_, AppError>`. That way you don't need to do that manually.
+// `Result<_, AppError>`, known error types are mapped to their status.
 impl<E> From<E> for AppError
 where
     E: Into<anyhow::Error>,
 {
     fn from(err: E) -> Self {
-        Self(err.into())
+        let err = err.into();
+
+        if let Some(errors) = err.downcast_ref::<ValidationErrors>() {
+            return Self::Validation {
+                message: "invalid input".to_owned(),
+                fields: validation_fields(errors),
+            };
+        }
+
+        if let Some(money) = err.downcast_ref::<MoneyError>() {
+            let field = match money {
+                MoneyError::InvalidCurrency(_) => "currency",
+                MoneyError::InvalidAmount(_) => "amount",
+            };
+
+            return Self::Validation {
+                message: "invalid input".to_owned(),
+                fields: BTreeMap::from([(field.to_owned(), vec![money.to_string()])]),
+            };
+        }
+
+        if let Some(message) = rejection_message(&err) {
+            return Self::Validation {
+                message,
+                fields: Default::default(),
+            };
+        }
+
+        if let Some(Forbidden(permission)) = err.downcast_ref::<Forbidden>() {
+            return Self::Forbidden(permission.to_owned());
+        }
+
+        if let Some(NotFound(message)) = err.downcast_ref::<NotFound>() {
+            return Self::NotFound(message.to_owned());
+        }
+
+        if let Some(evento::ReadError::NotFound) = err.downcast_ref::<evento::ReadError>() {
+            return Self::NotFound("not found".to_owned());
+        }
+
+        if let Some(Conflict(message)) = err.downcast_ref::<Conflict>() {
+            return Self::Conflict(message.to_owned());
+        }
+
+        Self::Internal(err)
+    }
+}
+
+fn validation_fields(errors: &ValidationErrors) -> BTreeMap<String, Vec<String>> {
+    errors
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            let messages = errors
+                .iter()
+                .map(|error| match &error.message {
+                    Some(message) => message.to_string(),
+                    _ => error.code.to_string(),
+                })
+                .collect();
+
+            (field.to_string(), messages)
+        })
+        .collect()
+}
+
+fn rejection_message(err: &anyhow::Error) -> Option<String> {
+    if let Some(rejection) = err.downcast_ref::<JsonRejection>() {
+        return Some(rejection.body_text());
+    }
+
+    if let Some(rejection) = err.downcast_ref::<QueryRejection>() {
+        return Some(rejection.body_text());
+    }
+
+    if let Some(rejection) = err.downcast_ref::<FormRejection>() {
+        return Some(rejection.body_text());
+    }
+
+    err.downcast_ref::<PathRejection>()
+        .map(|rejection| rejection.body_text())
+}
+
+#[derive(askama::Template)]
+#[template(path = "error.html")]
+pub struct ErrorTemplate {
+    pub title: String,
+    pub error: String,
+    pub fields: BTreeMap<String, Vec<String>>,
+}
+
+/// API requests are answered with JSON, the others with a localized page.
+fn wants_json(uri: &Uri, headers: &HeaderMap) -> bool {
+    uri.path().starts_with(crate::router::API)
+        || headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .is_some_and(|accept| accept.contains("application/json"))
+}
+
+/// Renders the `AppError` of the response, every route is wrapped by this middleware.
+pub async fn render(
+    html: Template<ErrorTemplate>,
+    forbidden: Template<ForbiddenTemplate>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let json = wants_json(request.uri(), request.headers());
+    let mut response = next.run(request).await;
+
+    let Some(ErrorExtension { status, body }) = response.extensions_mut().remove() else {
+        return response;
+    };
+
+    if json {
+        return (status, Json(body)).into_response();
+    }
+
+    if let Some(permission) = body.permission {
+        return (status, forbidden.template(ForbiddenTemplate { permission })).into_response();
+    }
+
+    let title = format!(
+        "{} {}",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default()
+    );
+
+    (
+        status,
+        html.template(ErrorTemplate {
+            title,
+            error: body.error,
+            fields: body.fields,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use timada_shared::{invalid_field, Money};
+
+    fn status(err: anyhow::Error) -> StatusCode {
+        AppError::from(err).status()
+    }
+
+    #[test]
+    fn conflicts_are_409() {
+        assert_eq!(
+            status(anyhow::anyhow!(Conflict::new("product not archived"))),
+            StatusCode::CONFLICT
+        );
+    }
+
+    #[test]
+    fn command_field_errors_are_422() {
+        let err = AppError::from(anyhow::anyhow!(invalid_field(
+            "amount",
+            "price cannot be negative"
+        )));
+
+        assert_eq!(err.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            err.body().fields.get("amount"),
+            Some(&vec!["price cannot be negative".to_owned()])
+        );
+    }
+
+    #[test]
+    fn money_errors_are_422() {
+        let err = Money::parse("12.5", "EURO").unwrap_err();
+        let err = AppError::from(anyhow::Error::from(err));
+
+        assert_eq!(err.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(err.body().fields.contains_key("currency"));
+    }
+
+    #[test]
+    fn not_found_and_forbidden() {
+        assert_eq!(
+            status(anyhow::anyhow!(NotFound::new("variant not found"))),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(anyhow::anyhow!(evento::ReadError::NotFound)),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(anyhow::anyhow!(Forbidden("market.product.read".to_owned()))),
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[test]
+    fn other_errors_are_500() {
+        assert_eq!(
+            status(anyhow::anyhow!("database is locked")),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
     }
 }
@@ -1,14 +1,22 @@
 mod assets;
 mod axum_extra;
 mod error;
+mod migration;
+mod migrator;
+mod oidc;
+mod projection;
 mod router;
+mod user;
 
+use ::axum_extra::extract::cookie::Key;
+use axum::extract::FromRef;
 use axum_extra::TemplateConfig;
 use clap::{arg, command, Command};
 use config::Config;
 use serde::Deserialize;
-use sqlx::{any::install_default_drivers, migrate::MigrateDatabase, SqlitePool};
-use sqlx_migrator::Migrate as _;
+use sqlx::{any::install_default_drivers, migrate::MigrateDatabase};
+use std::time::Duration;
+use timada_shared::{QueryPool, QueryPoolOptions};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 rust_i18n::i18n!("locales");
@@ -44,7 +52,84 @@ async fn main() -> anyhow::Result<()> {
         .subcommand(
             Command::new("migrate")
                 .about("Create timada database")
-                .arg(arg!(-c --config <FILE> "path to configuration file").required(true)),
+                .args_conflicts_with_subcommands(true)
+                .arg(arg!(--"dry-run" "print the SQL of pending migrations without applying them"))
+                .arg(arg!(-c --config <FILE> "path to configuration file").required(true))
+                .subcommand(
+                    Command::new("status")
+                        .about("List applied and pending migrations of both stores")
+                        .arg(arg!(-c --config <FILE> "path to configuration file").required(true)),
+                )
+                .subcommand(
+                    Command::new("up")
+                        .about("Apply pending migrations, up to and including --to")
+                        .arg(arg!(--to <NAME> "migration name, e.g. market_2026_10_17_19_45"))
+                        .arg(arg!(--"dry-run" "print the SQL without applying it"))
+                        .arg(arg!(-c --config <FILE> "path to configuration file").required(true)),
+                )
+                .subcommand(
+                    Command::new("revert")
+                        .about("Revert the last applied migrations of a store")
+                        .arg(
+                            arg!(--count <N> "number of migrations to revert")
+                                .value_parser(clap::value_parser!(usize))
+                                .default_value("1"),
+                        )
+                        .arg(
+                            arg!(--store <STORE> "store to revert")
+                                .value_parser(["query", "event"])
+                                .default_value("query"),
+                        )
+                        .arg(arg!(--"dry-run" "print the SQL without reverting it"))
+                        .arg(arg!(-c --config <FILE> "path to configuration file").required(true)),
+                ),
+        )
+        .subcommand(
+            Command::new("projection")
+                .about("Manage query projections")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("rebuild")
+                        .about("Empty a projection and replay every event into it")
+                        .arg(arg!(<NAME> "projection name, e.g. market.eu-west-3.product.query.products"))
+                        .arg(arg!(-c --config <FILE> "path to configuration file").required(true)),
+                )
+                .subcommand(
+                    Command::new("status")
+                        .about("Show cursor, lag and last error of every subscription")
+                        .arg(arg!(-c --config <FILE> "path to configuration file").required(true)),
+                ),
+        )
+        .subcommand(
+            Command::new("user")
+                .about("Manage admin users")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("create")
+                        .about("Register a user that can log in to the admin")
+                        .arg(arg!(--email <EMAIL> "email used to log in").required(true))
+                        .arg(arg!(--password <PASSWORD> "at least 8 characters").required(true))
+                        .arg(arg!(--owner <OWNER_ID> "shop or organisation the user belongs to"))
+                        .arg(
+                            arg!(--role <ROLE> "role granted to the user, can be repeated")
+                                .value_parser(["admin", "support"])
+                                .action(clap::ArgAction::Append)
+                                .default_value("admin"),
+                        )
+                        .arg(arg!(-c --config <FILE> "path to configuration file").required(true)),
+                )
+                .subcommand(
+                    Command::new("roles")
+                        .about("Replace the roles of a user")
+                        .arg(arg!(--email <EMAIL> "email of the user").required(true))
+                        .arg(
+                            arg!(--role <ROLE> "role granted to the user, can be repeated")
+                                .value_parser(["admin", "support"])
+                                .action(clap::ArgAction::Append)
+                                .required(true),
+                        )
+                        .arg(arg!(-c --config <FILE> "path to configuration file").required(true)),
+                ),
         )
         .subcommand(
             Command::new("reset")
@@ -77,14 +162,99 @@ async fn main() -> anyhow::Result<()> {
             }
         }
         Some(("migrate", sub_matches)) => {
+            let (sub_matches, action) = match sub_matches.subcommand() {
+                Some(("status", sub_matches)) => (sub_matches, migration::Action::Status),
+                Some(("up", sub_matches)) => (
+                    sub_matches,
+                    migration::Action::Up {
+                        to: sub_matches.get_one::<String>("to").cloned(),
+                    },
+                ),
+                Some(("revert", sub_matches)) => {
+                    let store = match sub_matches.get_one::<String>("store").map(String::as_str) {
+                        Some("event") => migration::Store::Event,
+                        _ => migration::Store::Query,
+                    };
+
+                    (
+                        sub_matches,
+                        migration::Action::Revert {
+                            store,
+                            count: *sub_matches.get_one::<usize>("count").expect("default"),
+                        },
+                    )
+                }
+                _ => (sub_matches, migration::Action::Up { to: None }),
+            };
+
+            let dry_run = matches!(sub_matches.try_get_one::<bool>("dry-run"), Ok(Some(true)));
             let config = sub_matches.get_one::<String>("config").expect("required");
             let config = expect_config(config);
-            if let Err(err) = migrate(config).await {
+            if let Err(err) = migration::run(config, action, dry_run).await {
                 tracing::error!("{err}");
 
                 std::process::exit(1);
             }
         }
+        Some(("projection", sub_matches)) => match sub_matches.subcommand() {
+            Some(("rebuild", sub_matches)) => {
+                let name = sub_matches.get_one::<String>("NAME").expect("required");
+                let config = sub_matches.get_one::<String>("config").expect("required");
+                let config = expect_config(config);
+                if let Err(err) = projection::rebuild(config, name).await {
+                    tracing::error!("{err}");
+
+                    std::process::exit(1);
+                }
+            }
+            Some(("status", sub_matches)) => {
+                let config = sub_matches.get_one::<String>("config").expect("required");
+                let config = expect_config(config);
+                if let Err(err) = projection::status(config).await {
+                    tracing::error!("{err}");
+
+                    std::process::exit(1);
+                }
+            }
+            _ => unreachable!("subcommand_required prevents `None`"),
+        },
+        Some(("user", sub_matches)) => match sub_matches.subcommand() {
+            Some(("create", sub_matches)) => {
+                let input = timada_identity::user::RegisterInput {
+                    email: sub_matches
+                        .get_one::<String>("email")
+                        .cloned()
+                        .expect("required"),
+                    password: sub_matches
+                        .get_one::<String>("password")
+                        .cloned()
+                        .expect("required"),
+                    owner_id: sub_matches.get_one::<String>("owner").cloned(),
+                    roles: user::roles(sub_matches),
+                };
+                let config = sub_matches.get_one::<String>("config").expect("required");
+                let config = expect_config(config);
+                if let Err(err) = user::create(config, input).await {
+                    tracing::error!("{err}");
+
+                    std::process::exit(1);
+                }
+            }
+            Some(("roles", sub_matches)) => {
+                let email = sub_matches.get_one::<String>("email").expect("required");
+                let input = timada_identity::user::ChangeRolesInput {
+                    roles: user::roles(sub_matches),
+                };
+                let config = sub_matches.get_one::<String>("config").expect("required");
+                let config = expect_config(config);
+                if let Err(err) = user::change_roles(config, email, input).await {
+                    tracing::error!("{err}");
+
+                    std::process::exit(1);
+                }
+            }
+            _ => unreachable!("subcommand_required prevents `None`"),
+        },
         #[cfg(debug_assertions)]
         Some(("reset", sub_matches)) => {
             let config = sub_matches.get_one::<String>("config").expect("required");
@@ -135,53 +305,191 @@ pub struct Serve {
     #[serde(rename = "data-dir")]
     pub data_dir: String,
     pub dsn: String,
+    #[serde(rename = "query-dsn")]
+    pub query_dsn: Option<String>,
+    #[serde(rename = "query-write-max-connections")]
+    pub query_write_max_connections: Option<u32>,
+    #[serde(rename = "query-write-busy-timeout")]
+    pub query_write_busy_timeout: Option<u64>,
+    #[serde(rename = "query-read-max-connections")]
+    pub query_read_max_connections: Option<u32>,
+    #[serde(rename = "query-read-busy-timeout")]
+    pub query_read_busy_timeout: Option<u64>,
+    /// Kept out of the config file, `TIMADA_SESSION_SECRET` sets it.
+    #[serde(rename = "session-secret", alias = "session_secret")]
+    pub session_secret: Option<String>,
+    /// Marks the session cookies `Secure`, only disable it when serving over plain http.
+    #[serde(rename = "secure-cookie", default = "default_secure_cookie")]
+    pub secure_cookie: bool,
+    pub oidc: Option<Oidc>,
+}
+
+fn default_secure_cookie() -> bool {
+    true
+}
+
+/// `[oidc]` section, enables the login through an OpenID Connect identity provider.
+#[derive(Deserialize, Clone)]
+pub struct Oidc {
+    #[serde(rename = "issuer-url")]
+    pub issuer_url: String,
+    #[serde(rename = "client-id")]
+    pub client_id: String,
+    /// Unset for public clients, PKCE is always used.
+    #[serde(rename = "client-secret")]
+    pub client_secret: Option<String>,
+    /// Must point to `/login/-/oidc/callback` of this server.
+    #[serde(rename = "redirect-url")]
+    pub redirect_url: String,
+    /// Requested in addition to `openid` and `email`.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// User info claim holding the owner id of users registered on their first login.
+    #[serde(rename = "owner-claim")]
+    pub owner_claim: Option<String>,
+    /// Roles of users registered on their first login.
+    #[serde(default = "default_oidc_roles")]
+    pub roles: Vec<String>,
+}
+
+fn default_oidc_roles() -> Vec<String> {
+    vec!["support".to_owned()]
+}
+
+impl Serve {
+    pub fn query_dsn(&self) -> String {
+        query_dsn(&self.query_dsn, &self.data_dir)
+    }
+
+    /// Pool of the projection handlers, busy timeouts are in milliseconds.
+    pub fn query_write_options(&self) -> QueryPoolOptions {
+        QueryPoolOptions {
+            max_connections: self.query_write_max_connections,
+            busy_timeout: self.query_write_busy_timeout.map(Duration::from_millis),
+            read_only: false,
+        }
+    }
+
+    /// Pool of the http handlers.
+    pub fn query_read_options(&self) -> QueryPoolOptions {
+        QueryPoolOptions {
+            max_connections: self.query_read_max_connections,
+            busy_timeout: self.query_read_busy_timeout.map(Duration::from_millis),
+            read_only: true,
+        }
+    }
+
+    /// Signs the session cookie. Without `session-secret`, a random key is used and sessions do
+    /// not survive a restart.
+    pub fn session_key(&self) -> anyhow::Result<Key> {
+        let Some(secret) = &self.session_secret else {
+            tracing::warn!("session-secret not set, sessions are lost on restart");
+
+            return Ok(Key::generate());
+        };
+
+        Key::try_from(secret.as_bytes())
+            .map_err(|_| anyhow::anyhow!("session-secret must be at least 64 bytes long"))
+    }
+}
+
+/// Projections are stored in `{data-dir}/query.sqlite3` unless `query-dsn` is set.
+fn query_dsn(query_dsn: &Option<String>, data_dir: &str) -> String {
+    query_dsn
+        .to_owned()
+        .unwrap_or_else(|| format!("sqlite:{data_dir}/query.sqlite3"))
 }
 
 #[derive(Clone)]
 pub struct State {
     pub config: Serve,
     pub evento: evento::Evento,
-    pub query_pool: SqlitePool,
+    /// Read-only, projections write through their own pool.
+    pub query_pool: QueryPool,
+    pub session_key: Key,
+    pub oidc: Option<oidc::Provider>,
 }
 
-pub async fn serve(config: Serve) -> anyhow::Result<()> {
-    let evento_executor: evento::Evento = if config.dsn.starts_with("sqlite:") {
-        let executor: evento::Sqlite = sqlx::SqlitePool::connect(&config.dsn).await?.into();
+impl FromRef<State> for Key {
+    fn from_ref(state: &State) -> Self {
+        state.session_key.clone()
+    }
+}
+
+pub async fn connect_evento(dsn: &str) -> anyhow::Result<evento::Evento> {
+    let executor: evento::Evento = if dsn.starts_with("sqlite:") {
+        let executor: evento::Sqlite = sqlx::SqlitePool::connect(dsn).await?.into();
         executor.into()
-    } else if config.dsn.starts_with("mysql:") {
-        let executor: evento::MySql = sqlx::MySqlPool::connect(&config.dsn).await?.into();
+    } else if dsn.starts_with("mysql:") {
+        let executor: evento::MySql = sqlx::MySqlPool::connect(dsn).await?.into();
         executor.into()
-    } else if config.dsn.starts_with("postgres:") {
-        let executor: evento::Postgres = sqlx::PgPool::connect(&config.dsn).await?.into();
+    } else if dsn.starts_with("postgres:") {
+        let executor: evento::Postgres = sqlx::PgPool::connect(dsn).await?.into();
         executor.into()
     } else {
-        anyhow::bail!(
-            "{} not supported, consider using Sqlite, MySql or Postgres",
-            config.dsn
-        )
+        anyhow::bail!("{dsn} not supported, consider using Sqlite, MySql or Postgres")
+    };
+
+    Ok(executor)
+}
+
+pub async fn serve(config: Serve) -> anyhow::Result<()> {
+    let evento_executor = connect_evento(&config.dsn).await?;
+    let session_key = config.session_key()?;
+    let oidc = match &config.oidc {
+        Some(oidc) => Some(oidc::Provider::discover(oidc).await?),
+        _ => None,
     };
 
     let query_db =
-        sqlx::SqlitePool::connect(&format!("{}/query.sqlite3", &config.data_dir)).await?;
+        QueryPool::connect_with(&config.query_dsn(), &config.query_write_options()).await?;
 
-    timada_market::product::subscribe_command(&config.region)
-        .run(&evento_executor)
-        .await?;
+    // Opened once the writer has switched sqlite to WAL.
+    let read_db =
+        QueryPool::connect_with(&config.query_dsn(), &config.query_read_options()).await?;
 
-    timada_market::product::subscribe_query_products(&config.region)?
-        .data(query_db.clone())
-        .run(&evento_executor)
-        .await?;
+    let (errors, errors_receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    for subscription in projection::Subscription::all() {
+        subscription
+            .subscribe(&config.region, errors.clone())?
+            .data(query_db.clone())
+            .run(&evento_executor)
+            .await?;
+    }
+
+    tokio::spawn(projection::record_errors(query_db.clone(), errors_receiver));
+
+    let price_executor = evento_executor.clone();
+    let price_db = query_db.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) =
+                timada_market::price::apply_due_prices(&price_executor, &price_db).await
+            {
+                tracing::error!("apply_due_prices '{err}'");
+            }
+        }
+    });
 
     let addr = config.addr.to_owned();
 
-    let mut app = router::create_router()
-        .layer(TemplateConfig::new(&config.assets_base_url))
-        .with_state(State {
-            config,
-            evento: evento_executor.clone(),
-            query_pool: query_db, // should be read sqlite ?
-        });
+    let assets_base_url = config.assets_base_url.to_owned();
+    let state = State {
+        config,
+        evento: evento_executor.clone(),
+        query_pool: read_db,
+        session_key,
+        oidc,
+    };
+
+    let mut app = router::create_router(&state)
+        .layer(TemplateConfig::new(&assets_base_url))
+        .with_state(state);
 
     #[cfg(debug_assertions)]
     {
@@ -200,57 +508,14 @@ struct Migrate {
     #[serde(rename = "data-dir")]
     pub data_dir: String,
     pub dsn: String,
+    #[serde(rename = "query-dsn")]
+    pub query_dsn: Option<String>,
 }
 
-async fn migrate(config: Migrate) -> anyhow::Result<()> {
-    install_default_drivers();
-
-    if let Err(err) = sqlx::any::Any::create_database(&config.dsn).await {
-        tracing::warn!("{err}");
-    };
-
-    if config.dsn.starts_with("sqlite:") {
-        let pool = sqlx::SqlitePool::connect(&config.dsn).await?;
-        let mut conn = pool.acquire().await?;
-        let evento_migrator = evento::sql_migrator::new_migrator::<sqlx::Sqlite>()?;
-        evento_migrator
-            .run(&mut *conn, &sqlx_migrator::Plan::apply_all())
-            .await?;
-    } else if config.dsn.starts_with("mysql:") {
-        let pool = sqlx::MySqlPool::connect(&config.dsn).await?;
-        let mut conn = pool.acquire().await?;
-        let evento_migrator = evento::sql_migrator::new_migrator::<sqlx::MySql>()?;
-        evento_migrator
-            .run(&mut *conn, &sqlx_migrator::Plan::apply_all())
-            .await?;
-    } else if config.dsn.starts_with("postgres:") {
-        let pool = sqlx::PgPool::connect(&config.dsn).await?;
-        let mut conn = pool.acquire().await?;
-        let evento_migrator = evento::sql_migrator::new_migrator::<sqlx::Postgres>()?;
-        evento_migrator
-            .run(&mut *conn, &sqlx_migrator::Plan::apply_all())
-            .await?;
-    } else {
-        anyhow::bail!(
-            "{} not supported, consider using Sqlite, MySql or Postgres",
-            config.dsn
-        )
+impl Migrate {
+    pub fn query_dsn(&self) -> String {
+        query_dsn(&self.query_dsn, &self.data_dir)
     }
-
-    let dsn = format!("{}/query.sqlite3", config.data_dir);
-    if let Err(err) = sqlx::Sqlite::create_database(&dsn).await {
-        tracing::warn!("{err}");
-    };
-
-    let pool = sqlx::SqlitePool::connect(&dsn).await?;
-    let mut conn = pool.acquire().await?;
-    let mut migrator = sqlx_migrator::Migrator::default();
-    timada_market::migrator::add_migrations(&mut migrator)?;
-    migrator
-        .run(&mut *conn, &sqlx_migrator::Plan::apply_all())
-        .await?;
-
-    Ok(())
 }
 
 #[cfg(debug_assertions)]
@@ -261,10 +526,9 @@ async fn reset(config: Migrate) -> anyhow::Result<()> {
         tracing::warn!("{err}");
     };
 
-    let dsn = format!("{}/query.sqlite3", config.data_dir);
-    if let Err(err) = sqlx::Sqlite::drop_database(&dsn).await {
+    if let Err(err) = sqlx::any::Any::drop_database(&config.query_dsn()).await {
         tracing::warn!("{err}");
     };
 
-    migrate(config).await
+    migration::run(config, migration::Action::Up { to: None }, false).await
 }
@@ -0,0 +1,532 @@
+use evento::sql::{Event, Snapshot, Subscriber};
+use sea_query::{ColumnDef, Expr, Index, Table};
+use sqlx::{any::install_default_drivers, migrate::MigrateDatabase, Database};
+use sqlx_migrator::{migration::Migration, Plan};
+use timada_shared::{Backend, QueryMigrator, Statements};
+
+use crate::Migrate;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Store {
+    Event,
+    Query,
+}
+
+impl Store {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Event => "event",
+            Self::Query => "query",
+        }
+    }
+}
+
+/// What `timada migrate` does, `status` and `up` go through both stores, `revert` only the one
+/// it is given.
+pub enum Action {
+    Status,
+    Up { to: Option<String> },
+    Revert { store: Store, count: usize },
+}
+
+/// Statements `--dry-run` prints for a migration, reverted or not. `None` when they are not
+/// known, an evento migration this file does not mirror yet.
+type Render<'a, DB> = &'a dyn Fn(&dyn Migration<DB>, bool) -> Option<Vec<String>>;
+
+/// Statements of the event store migrations, evento only runs them.
+fn event_statements<DB: Database>(
+    backend: Backend,
+) -> impl Fn(&dyn Migration<DB>, bool) -> Option<Vec<String>> {
+    move |migration, revert| {
+        if (migration.app(), migration.name()) != ("main", "init_migration") {
+            return None;
+        }
+
+        Some(match revert {
+            true => EventInitStatements.down(backend),
+            _ => EventInitStatements.up(backend),
+        })
+    }
+}
+
+/// Mirrors `evento::sql_migrator::InitMigration`, keep both in sync when evento is upgraded.
+struct EventInitStatements;
+
+impl Statements for EventInitStatements {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        let builder = backend.schema_builder();
+
+        vec![
+            Table::create()
+                .table(Event::Table)
+                .col(
+                    ColumnDef::new(Event::Id)
+                        .string()
+                        .not_null()
+                        .string_len(26)
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Event::Name)
+                        .string()
+                        .string_len(20)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Event::AggregatorType)
+                        .string()
+                        .string_len(50)
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Event::AggregatorId)
+                        .string()
+                        .string_len(26)
+                        .not_null(),
+                )
+                .col(ColumnDef::new(Event::Version).integer().not_null())
+                .col(ColumnDef::new(Event::Data).blob().not_null())
+                .col(ColumnDef::new(Event::Metadata).blob().not_null())
+                .col(ColumnDef::new(Event::RoutingKey).string().string_len(50))
+                .col(ColumnDef::new(Event::Timestamp).big_integer().not_null())
+                .build_any(builder),
+            Index::create()
+                .name("idx_event_type")
+                .table(Event::Table)
+                .col(Event::AggregatorType)
+                .build_any(builder),
+            Index::create()
+                .name("idx_event_type_id")
+                .table(Event::Table)
+                .col(Event::AggregatorType)
+                .col(Event::AggregatorId)
+                .build_any(builder),
+            Index::create()
+                .name("idx_event_routing_key_type")
+                .table(Event::Table)
+                .col(Event::RoutingKey)
+                .col(Event::AggregatorType)
+                .build_any(builder),
+            Index::create()
+                .name("idx_event_type_id_version")
+                .table(Event::Table)
+                .unique()
+                .col(Event::AggregatorType)
+                .col(Event::AggregatorId)
+                .col(Event::Version)
+                .build_any(builder),
+            Table::create()
+                .table(Snapshot::Table)
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Snapshot::Id)
+                        .string()
+                        .not_null()
+                        .string_len(26),
+                )
+                .col(
+                    ColumnDef::new(Snapshot::Type)
+                        .string()
+                        .string_len(50)
+                        .not_null(),
+                )
+                .col(ColumnDef::new(Snapshot::Cursor).string().not_null())
+                .col(ColumnDef::new(Snapshot::Revision).string().not_null())
+                .col(ColumnDef::new(Snapshot::Data).blob().not_null())
+                .col(
+                    ColumnDef::new(Snapshot::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Snapshot::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .primary_key(Index::create().col(Snapshot::Type).col(Snapshot::Id))
+                .build_any(builder),
+            Table::create()
+                .table(Subscriber::Table)
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Subscriber::Key)
+                        .string()
+                        .string_len(50)
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Subscriber::WorkerId)
+                        .string()
+                        .not_null()
+                        .string_len(26),
+                )
+                .col(ColumnDef::new(Subscriber::Cursor).string())
+                .col(ColumnDef::new(Subscriber::Lag).integer().not_null())
+                .col(
+                    ColumnDef::new(Subscriber::Enabled)
+                        .boolean()
+                        .not_null()
+                        .default(true),
+                )
+                .col(
+                    ColumnDef::new(Subscriber::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Subscriber::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .build_any(builder),
+        ]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        let builder = backend.schema_builder();
+
+        vec![
+            Table::drop().table(Subscriber::Table).build_any(builder),
+            Table::drop().table(Snapshot::Table).build_any(builder),
+            Index::drop()
+                .name("idx_event_type_id_version")
+                .table(Event::Table)
+                .build_any(builder),
+            Index::drop()
+                .name("idx_event_routing_key_type")
+                .table(Event::Table)
+                .build_any(builder),
+            Index::drop()
+                .name("idx_event_type_id")
+                .table(Event::Table)
+                .build_any(builder),
+            Index::drop()
+                .name("idx_event_type")
+                .table(Event::Table)
+                .build_any(builder),
+            Table::drop().table(Event::Table).build_any(builder),
+        ]
+    }
+}
+
+pub async fn run(config: Migrate, action: Action, dry_run: bool) -> anyhow::Result<()> {
+    install_default_drivers();
+
+    let query_dsn = config.query_dsn();
+
+    if let (Action::Up { .. }, false) = (&action, dry_run) {
+        for dsn in [&config.dsn, &query_dsn] {
+            if let Err(err) = sqlx::any::Any::create_database(dsn).await {
+                tracing::warn!("{err}");
+            };
+        }
+    }
+
+    if let Action::Status = &action {
+        println!(
+            "{:<6} {:<6} {:<30} {:<8} APPLIED AT",
+            "STORE", "APP", "NAME", "STATUS"
+        );
+    }
+
+    let found = if config.dsn.starts_with("sqlite:") {
+        let pool = sqlx::SqlitePool::connect(&config.dsn).await?;
+        let mut conn = pool.acquire().await?;
+        let migrator = evento::sql_migrator::new_migrator::<sqlx::Sqlite>()?;
+        run_store(
+            Store::Event,
+            &mut *conn,
+            &migrator,
+            &action,
+            dry_run.then_some(&event_statements(Backend::Sqlite) as Render<'_, sqlx::Sqlite>),
+        )
+        .await?
+    } else if config.dsn.starts_with("mysql:") {
+        let pool = sqlx::MySqlPool::connect(&config.dsn).await?;
+        let mut conn = pool.acquire().await?;
+        let migrator = evento::sql_migrator::new_migrator::<sqlx::MySql>()?;
+        run_store(
+            Store::Event,
+            &mut *conn,
+            &migrator,
+            &action,
+            dry_run.then_some(&event_statements(Backend::MySql) as Render<'_, sqlx::MySql>),
+        )
+        .await?
+    } else if config.dsn.starts_with("postgres:") {
+        let pool = sqlx::PgPool::connect(&config.dsn).await?;
+        let mut conn = pool.acquire().await?;
+        let migrator = evento::sql_migrator::new_migrator::<sqlx::Postgres>()?;
+        run_store(
+            Store::Event,
+            &mut *conn,
+            &migrator,
+            &action,
+            dry_run.then_some(&event_statements(Backend::Postgres) as Render<'_, sqlx::Postgres>),
+        )
+        .await?
+    } else {
+        anyhow::bail!(
+            "{} not supported, consider using Sqlite, MySql or Postgres",
+            config.dsn
+        )
+    };
+
+    let pool = sqlx::AnyPool::connect(&query_dsn).await?;
+    let mut conn = pool.acquire().await?;
+    let mut migrator = QueryMigrator::default();
+    add_query_migrations(&mut migrator)?;
+    let backend = Backend::of(&conn)?;
+    let render: Render<'_, sqlx::Any> =
+        &|migration, revert| Some(migrator.statements(migration, backend, revert));
+    let found = run_store(
+        Store::Query,
+        &mut *conn,
+        &migrator,
+        &action,
+        dry_run.then_some(render),
+    )
+    .await?
+        || found;
+
+    if let Action::Up { to: Some(name) } = &action {
+        if !found {
+            anyhow::bail!("migration '{name}' not found");
+        }
+    }
+
+    Ok(())
+}
+
+fn add_query_migrations(migrator: &mut QueryMigrator) -> Result<(), sqlx_migrator::Error> {
+    timada_market::migrator::add_migrations(migrator)?;
+    timada_inventory::migrator::add_migrations(migrator)?;
+    timada_identity::migrator::add_migrations(migrator)?;
+    crate::migrator::add_migrations(migrator)?;
+
+    Ok(())
+}
+
+/// Returns false when the store is left untouched, `--to` names a migration of the other store
+/// or `revert` targets the other one.
+async fn run_store<DB: Database>(
+    store: Store,
+    connection: &mut DB::Connection,
+    migrator: &dyn sqlx_migrator::Migrate<DB>,
+    action: &Action,
+    dry_run: Option<Render<'_, DB>>,
+) -> anyhow::Result<bool> {
+    let plan = match action {
+        Action::Status => {
+            print_status(store, connection, migrator).await?;
+
+            return Ok(true);
+        }
+        Action::Up { to: None } => Plan::apply_all(),
+        Action::Up { to: Some(name) } => {
+            let Some(migration) = migrator.migrations().iter().find(|m| m.name() == name) else {
+                return Ok(false);
+            };
+
+            migrator.ensure_migration_table_exists(connection).await?;
+
+            let applied = migrator.fetch_applied_migration_from_db(connection).await?;
+
+            if applied.iter().any(|row| row == migration) {
+                println!("{}: {name} already applied", store.name());
+
+                return Ok(true);
+            }
+
+            Plan::apply_name(migration.app(), &Some(name.to_owned()))
+        }
+        Action::Revert {
+            store: revert_store,
+            count,
+        } => {
+            if *revert_store != store {
+                return Ok(false);
+            }
+
+            Plan::revert_count(*count)
+        }
+    };
+
+    if let Some(render) = dry_run {
+        let revert = matches!(action, Action::Revert { .. });
+        print_plan(store, connection, migrator, &plan, revert, render).await?;
+
+        return Ok(true);
+    }
+
+    let migrations = migrator
+        .generate_migration_plan(connection, Some(&plan))
+        .await?;
+
+    let verb = match action {
+        Action::Revert { .. } => "reverting",
+        _ => "applying",
+    };
+
+    for migration in migrations.iter() {
+        println!(
+            "{}: {verb} {} {}",
+            store.name(),
+            migration.app(),
+            migration.name()
+        );
+    }
+
+    migrator.run(connection, &plan).await?;
+
+    Ok(true)
+}
+
+/// Prints the statements of the migrations in the plan, nothing is run but the creation of the
+/// migration table when it is missing, as `status` does.
+async fn print_plan<DB: Database>(
+    store: Store,
+    connection: &mut DB::Connection,
+    migrator: &dyn sqlx_migrator::Migrate<DB>,
+    plan: &Plan,
+    revert: bool,
+    render: Render<'_, DB>,
+) -> anyhow::Result<()> {
+    migrator.ensure_migration_table_exists(connection).await?;
+
+    let migrations = migrator
+        .generate_migration_plan(connection, Some(plan))
+        .await?;
+
+    for migration in migrations {
+        println!(
+            "-- {}: {} {}",
+            store.name(),
+            migration.app(),
+            migration.name()
+        );
+
+        match render(&**migration, revert) {
+            Some(statements) => {
+                for statement in statements {
+                    println!("{statement};");
+                }
+            }
+            _ => println!("-- statements of this evento migration are not known"),
+        }
+
+        println!();
+    }
+
+    Ok(())
+}
+
+async fn print_status<DB: Database>(
+    store: Store,
+    connection: &mut DB::Connection,
+    migrator: &dyn sqlx_migrator::Migrate<DB>,
+) -> anyhow::Result<()> {
+    migrator.ensure_migration_table_exists(connection).await?;
+
+    let migrations = migrator.generate_migration_plan(connection, None).await?;
+    let applied = migrator.fetch_applied_migration_from_db(connection).await?;
+
+    for migration in migrations {
+        let (status, applied_at) = match applied.iter().find(|row| *row == migration) {
+            Some(row) => ("applied", row.applied_time().to_owned()),
+            _ => ("pending", "-".to_owned()),
+        };
+
+        println!(
+            "{:<6} {:<6} {:<30} {status:<8} {applied_at}",
+            store.name(),
+            migration.app(),
+            migration.name()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Connection;
+    use sqlx_migrator::{migrator::DatabaseOperation, Info, Migrate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn query_migrations_revert_on_sqlite() -> anyhow::Result<()> {
+        install_default_drivers();
+
+        let mut conn = sqlx::AnyConnection::connect("sqlite::memory:").await?;
+        let mut migrator = QueryMigrator::default();
+        add_query_migrations(&mut migrator)?;
+
+        migrator.run(&mut conn, &Plan::apply_all()).await?;
+        assert_eq!(
+            migrator
+                .fetch_applied_migration_from_db(&mut conn)
+                .await?
+                .len(),
+            migrator.migrations().len()
+        );
+
+        migrator.run(&mut conn, &Plan::revert_all()).await?;
+        assert!(migrator
+            .fetch_applied_migration_from_db(&mut conn)
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn event_store_dry_run_prints_the_evento_tables() -> anyhow::Result<()> {
+        let migrator = evento::sql_migrator::new_migrator::<sqlx::Postgres>()?;
+        let render = event_statements::<sqlx::Postgres>(Backend::Postgres);
+
+        for migration in migrator.migrations() {
+            let statements = render(&**migration, false).unwrap_or_default();
+            assert!(statements
+                .iter()
+                .any(|statement| statement.starts_with("CREATE TABLE")));
+        }
+
+        Ok(())
+    }
+
+    /// Sqlite keeps the statements that created its schema, both must be the same.
+    #[tokio::test]
+    async fn event_store_dry_run_matches_evento() -> anyhow::Result<()> {
+        async fn schema(conn: &mut sqlx::SqliteConnection) -> anyhow::Result<Vec<String>> {
+            Ok(sqlx::query_as::<_, (String,)>(
+                "SELECT sql FROM sqlite_master WHERE sql IS NOT NULL AND name NOT LIKE '_sqlx%' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+            )
+            .fetch_all(conn)
+            .await?
+            .into_iter()
+            .map(|(sql,)| sql)
+            .collect())
+        }
+
+        let mut evento_conn = sqlx::SqliteConnection::connect("sqlite::memory:").await?;
+        let migrator = evento::sql_migrator::new_migrator::<sqlx::Sqlite>()?;
+        migrator.run(&mut evento_conn, &Plan::apply_all()).await?;
+
+        let mut conn = sqlx::SqliteConnection::connect("sqlite::memory:").await?;
+        let render = event_statements::<sqlx::Sqlite>(Backend::Sqlite);
+        for migration in migrator.migrations() {
+            for statement in render(&**migration, false).unwrap_or_default() {
+                sqlx::query(&statement).execute(&mut conn).await?;
+            }
+        }
+
+        assert_eq!(schema(&mut conn).await?, schema(&mut evento_conn).await?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+mod timada_2026_10_17_22_30;
+
+use timada_2026_10_17_22_30::Timada202610172230;
+use timada_shared::QueryMigrator;
+
+pub fn add_migrations(migrator: &mut QueryMigrator) -> Result<(), sqlx_migrator::Error> {
+    migrator.add_migration(Timada202610172230)?;
+
+    Ok(())
+}
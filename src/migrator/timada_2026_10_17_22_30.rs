@@ -0,0 +1,47 @@
+use crate::projection::SubscriptionErrorIden;
+use sea_query::{ColumnDef, Table};
+use sqlx_migrator::vec_box;
+use timada_shared::{Backend, Statements};
+
+pub struct CreateSubscriptionErrorTableOperation;
+
+impl Statements for CreateSubscriptionErrorTableOperation {
+    fn up(&self, backend: Backend) -> Vec<String> {
+        vec![Table::create()
+            .table(SubscriptionErrorIden::Table)
+            .col(
+                ColumnDef::new(SubscriptionErrorIden::Key)
+                    .string()
+                    .string_len(255)
+                    .primary_key()
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(SubscriptionErrorIden::Error)
+                    .text()
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(SubscriptionErrorIden::CreatedAt)
+                    .big_integer()
+                    .not_null(),
+            )
+            .build_any(backend.schema_builder())]
+    }
+
+    fn down(&self, backend: Backend) -> Vec<String> {
+        vec![Table::drop()
+            .table(SubscriptionErrorIden::Table)
+            .build_any(backend.schema_builder())]
+    }
+}
+
+pub struct Timada202610172230;
+
+timada_shared::query_migration!(
+    Timada202610172230,
+    "main",
+    "timada_2026_10_17_22_30",
+    vec_box![],
+    vec_box![CreateSubscriptionErrorTableOperation]
+);
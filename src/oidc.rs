@@ -0,0 +1,236 @@
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    SignedCookieJar,
+};
+use openidconnect::{
+    core::{CoreClient, CoreGenderClaim, CoreProviderMetadata, CoreResponseType},
+    reqwest, AdditionalClaims, AuthenticationFlow, AuthorizationCode, ClientId, ClientSecret,
+    CsrfToken, EndpointMaybeSet, EndpointNotSet, EndpointSet, IssuerUrl, Nonce,
+    OAuth2TokenResponse, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope, TokenResponse,
+    UserInfoClaims,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+use timada_identity::user::Role;
+
+use crate::Oidc;
+
+const COOKIE_NAME: &str = "timada_oidc";
+const MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+type Client = CoreClient<
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
+/// Claims of the user info endpoint that are not part of the standard ones, the owner claim is
+/// read from them.
+#[derive(Debug, Serialize, Deserialize)]
+struct OtherClaims(HashMap<String, serde_json::Value>);
+
+impl AdditionalClaims for OtherClaims {}
+
+/// Identity provider of the `[oidc]` section, discovered once on startup.
+#[derive(Clone)]
+pub struct Provider {
+    client: Client,
+    http_client: reqwest::Client,
+    scopes: Vec<String>,
+    owner_claim: Option<String>,
+    pub roles: Vec<Role>,
+}
+
+impl Provider {
+    pub async fn discover(config: &Oidc) -> anyhow::Result<Self> {
+        // Following redirects opens the client up to SSRF vulnerabilities.
+        let http_client = reqwest::ClientBuilder::new()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        let metadata = CoreProviderMetadata::discover_async(
+            IssuerUrl::new(config.issuer_url.to_owned())?,
+            &http_client,
+        )
+        .await?;
+
+        let client = CoreClient::from_provider_metadata(
+            metadata,
+            ClientId::new(config.client_id.to_owned()),
+            config.client_secret.to_owned().map(ClientSecret::new),
+        )
+        .set_redirect_uri(RedirectUrl::new(config.redirect_url.to_owned())?);
+
+        let roles = config
+            .roles
+            .iter()
+            .map(|role| {
+                role.parse::<Role>()
+                    .map_err(|_| anyhow::anyhow!("oidc role '{role}' not supported"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            client,
+            http_client,
+            scopes: config.scopes.to_owned(),
+            owner_claim: config.owner_claim.to_owned(),
+            roles,
+        })
+    }
+
+    /// Url of the identity provider login page, the returned `Pending` has to be kept in a cookie
+    /// until the callback.
+    pub fn authorize_url(&self) -> (String, Pending) {
+        let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
+
+        let mut request = self
+            .client
+            .authorize_url(
+                AuthenticationFlow::<CoreResponseType>::AuthorizationCode,
+                CsrfToken::new_random,
+                Nonce::new_random,
+            )
+            .add_scope(Scope::new("email".to_owned()))
+            .set_pkce_challenge(challenge);
+
+        for scope in &self.scopes {
+            request = request.add_scope(Scope::new(scope.to_owned()));
+        }
+
+        let (url, csrf, nonce) = request.url();
+
+        (
+            url.to_string(),
+            Pending {
+                csrf,
+                nonce,
+                verifier,
+            },
+        )
+    }
+
+    /// Exchanges the authorization code and verifies the id token of the callback.
+    pub async fn claims(&self, pending: Pending, callback: Callback) -> anyhow::Result<Claims> {
+        if let Some(error) = callback.error {
+            anyhow::bail!("identity provider error '{error}'");
+        }
+
+        let Some(code) = callback.code else {
+            anyhow::bail!("identity provider did not return a code");
+        };
+
+        if callback.state.as_deref() != Some(pending.csrf.secret().as_str()) {
+            anyhow::bail!("invalid oidc state");
+        }
+
+        let token = self
+            .client
+            .exchange_code(AuthorizationCode::new(code))?
+            .set_pkce_verifier(pending.verifier)
+            .request_async(&self.http_client)
+            .await?;
+
+        let Some(id_token) = token.id_token() else {
+            anyhow::bail!("identity provider did not return an id token");
+        };
+
+        let claims = id_token.claims(&self.client.id_token_verifier(), &pending.nonce)?;
+
+        // Accounts are linked by email, an unverified one could take over any of them.
+        if claims.email_verified() != Some(true) {
+            anyhow::bail!("email not verified by the identity provider");
+        }
+
+        let Some(email) = claims.email() else {
+            anyhow::bail!("identity provider did not return an email");
+        };
+
+        let email = email.as_str().to_owned();
+
+        let Some(owner_claim) = &self.owner_claim else {
+            return Ok(Claims {
+                email,
+                owner_id: None,
+            });
+        };
+
+        let user_info: UserInfoClaims<OtherClaims, CoreGenderClaim> = self
+            .client
+            .user_info(
+                token.access_token().to_owned(),
+                Some(claims.subject().to_owned()),
+            )?
+            .request_async(&self.http_client)
+            .await?;
+
+        let owner_id = user_info
+            .additional_claims()
+            .0
+            .get(owner_claim)
+            .and_then(|value| value.as_str())
+            .map(str::to_owned);
+
+        Ok(Claims { email, owner_id })
+    }
+}
+
+/// Query of the identity provider redirect.
+#[derive(Deserialize)]
+pub struct Callback {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Claims mapped to the user of the session.
+pub struct Claims {
+    pub email: String,
+    pub owner_id: Option<String>,
+}
+
+/// State, nonce and PKCE verifier of an authorization in progress.
+pub struct Pending {
+    csrf: CsrfToken,
+    nonce: Nonce,
+    verifier: PkceCodeVerifier,
+}
+
+impl Pending {
+    /// Values are base64url encoded and can not contain `:`.
+    pub fn cookie(&self, secure: bool) -> Cookie<'static> {
+        Cookie::build((
+            COOKIE_NAME,
+            format!(
+                "{}:{}:{}",
+                self.csrf.secret(),
+                self.nonce.secret(),
+                self.verifier.secret()
+            ),
+        ))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .secure(secure)
+        .max_age(MAX_AGE.try_into().unwrap_or_default())
+        .build()
+    }
+
+    pub fn removal_cookie() -> Cookie<'static> {
+        Cookie::build(COOKIE_NAME).path("/").build()
+    }
+
+    pub fn from_jar(jar: &SignedCookieJar) -> Option<Self> {
+        let cookie = jar.get(COOKIE_NAME)?;
+        let mut values = cookie.value().splitn(3, ':');
+
+        Some(Self {
+            csrf: CsrfToken::new(values.next()?.to_owned()),
+            nonce: Nonce::new(values.next()?.to_owned()),
+            verifier: PkceCodeVerifier::new(values.next()?.to_owned()),
+        })
+    }
+}
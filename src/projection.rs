@@ -0,0 +1,483 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use evento::{
+    cursor::{Args, Cursor},
+    AggregatorName, Executor, RoutingKey, SubscribeBuilder,
+};
+use sea_query::{Expr, ExprTrait, OnConflict, Query};
+use sqlx::FromRow;
+use timada_identity::{token::Token, user::User};
+use timada_inventory::stock::Stock;
+use timada_market::{category::Category, product::Product};
+use timada_shared::{HandlerErrors, QueryPool};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use ulid::Ulid;
+
+use crate::Serve;
+
+#[derive(Default, Debug, Clone, FromRow)]
+#[sea_query::enum_def]
+pub struct SubscriptionError {
+    pub key: String,
+    pub error: String,
+    pub created_at: i64,
+}
+
+impl SubscriptionError {
+    pub fn created_at_utc(&self) -> String {
+        timada_market::price::format_timestamp(self.created_at)
+    }
+}
+
+/// Every subscription started by `timada serve`, in the order they are started.
+#[derive(Clone, Copy)]
+pub enum Subscription {
+    ProductCommand,
+    Products,
+    Variants,
+    Prices,
+    CategoryCommand,
+    Categories,
+    StockCommand,
+    Stocks,
+    Users,
+    Tokens,
+}
+
+impl Subscription {
+    pub fn all() -> [Self; 10] {
+        [
+            Self::ProductCommand,
+            Self::Products,
+            Self::Variants,
+            Self::Prices,
+            Self::CategoryCommand,
+            Self::Categories,
+            Self::StockCommand,
+            Self::Stocks,
+            Self::Users,
+            Self::Tokens,
+        ]
+    }
+
+    pub fn key(&self, region: &str) -> String {
+        match self {
+            Self::ProductCommand => format!("market.{region}.product.command"),
+            Self::Products => format!("market.{region}.product.query.products"),
+            Self::Variants => format!("market.{region}.product.query.variants"),
+            Self::Prices => format!("market.{region}.product.query.prices"),
+            Self::CategoryCommand => format!("market.{region}.category.command"),
+            Self::Categories => format!("market.{region}.category.query.categories"),
+            Self::StockCommand => format!("inventory.{region}.stock.command"),
+            Self::Stocks => format!("inventory.{region}.stock.query.stocks"),
+            Self::Users => format!("identity.{region}.user.query.users"),
+            Self::Tokens => format!("identity.{region}.token.query.tokens"),
+        }
+    }
+
+    pub fn projections() -> impl Iterator<Item = Self> {
+        Self::all()
+            .into_iter()
+            .filter(|subscription| subscription.is_projection())
+    }
+
+    pub fn find_projection(region: &str, key: &str) -> Option<Self> {
+        Self::projections().find(|projection| projection.key(region) == key)
+    }
+
+    pub fn is_projection(&self) -> bool {
+        !matches!(
+            self,
+            Self::ProductCommand | Self::CategoryCommand | Self::StockCommand
+        )
+    }
+
+    pub fn aggregator_types(&self) -> HashSet<String> {
+        let names = match self {
+            Self::ProductCommand
+            | Self::Products
+            | Self::Variants
+            | Self::Prices
+            | Self::StockCommand => vec![Product::name()],
+            Self::CategoryCommand => vec![Category::name(), Product::name()],
+            Self::Categories => vec![Category::name()],
+            Self::Stocks => vec![Stock::name()],
+            Self::Users => vec![User::name()],
+            Self::Tokens => vec![Token::name()],
+        };
+
+        names.into_iter().map(str::to_owned).collect()
+    }
+
+    /// The errors of its handlers are sent to `errors`, `record_errors` stores them.
+    pub fn subscribe<E: Executor + Clone>(
+        &self,
+        region: &str,
+        errors: UnboundedSender<SubscriptionError>,
+    ) -> anyhow::Result<SubscribeBuilder<E>> {
+        let builder = match self {
+            Self::ProductCommand => timada_market::product::subscribe_command(region),
+            Self::Products => timada_market::product::subscribe_query_products(region)?,
+            Self::Variants => timada_market::variant::subscribe_query_variants(region)?,
+            Self::Prices => timada_market::price::subscribe_query_prices(region)?,
+            Self::CategoryCommand => timada_market::category::subscribe_command(region),
+            Self::Categories => timada_market::category::subscribe_query_categories(region)?,
+            Self::StockCommand => timada_inventory::stock::subscribe_command(region),
+            Self::Stocks => timada_inventory::stock::subscribe_query_stocks(region)?,
+            Self::Users => timada_identity::user::subscribe_query_users(region)?,
+            Self::Tokens => timada_identity::token::subscribe_query_tokens(region)?,
+        };
+
+        let key = self.key(region);
+
+        Ok(builder.data(HandlerErrors::new(move |event, err| {
+            let _ = errors.send(SubscriptionError {
+                key: key.to_owned(),
+                error: format!(
+                    "id='{}' type='{}' event_name='{}' error='{err}'",
+                    event.id, event.aggregator_type, event.name
+                ),
+                created_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs() as i64)
+                    .unwrap_or_default(),
+            });
+        })))
+    }
+
+    /// Empties the tables of the projection before its subscription replays every event.
+    pub async fn reset(&self, pool: &QueryPool) -> anyhow::Result<()> {
+        match self {
+            Self::Products => timada_market::product::reset_query_products(pool).await,
+            Self::Variants => timada_market::variant::reset_query_variants(pool).await,
+            Self::Prices => timada_market::price::reset_query_prices(pool).await,
+            Self::Categories => timada_market::category::reset_query_categories(pool).await,
+            Self::Stocks => timada_inventory::stock::reset_query_stocks(pool).await,
+            Self::Users => timada_identity::user::reset_query_users(pool).await,
+            Self::Tokens => timada_identity::token::reset_query_tokens(pool).await,
+            Self::ProductCommand | Self::CategoryCommand | Self::StockCommand => {
+                anyhow::bail!("command subscriptions have no projection to reset")
+            }
+        }
+    }
+}
+
+/// Position of every event a projection subscribes to, in the order evento delivers them.
+async fn read_positions<E: Executor>(
+    executor: &E,
+    subscription: Subscription,
+    region: &str,
+) -> anyhow::Result<Vec<(i64, i32, ulid::Ulid)>> {
+    let mut positions = vec![];
+    let mut after = None;
+
+    loop {
+        let events = executor
+            .read(
+                subscription.aggregator_types(),
+                RoutingKey::Value(Some(region.to_owned())),
+                Args::forward(1000, after),
+            )
+            .await?;
+
+        positions.extend(
+            events
+                .edges
+                .iter()
+                .map(|edge| (edge.node.timestamp, edge.node.version, edge.node.id)),
+        );
+
+        if !events.page_info.has_next_page {
+            break;
+        }
+
+        after = events.page_info.end_cursor;
+    }
+
+    Ok(positions)
+}
+
+async fn reset_subscriber_cursor(dsn: &str, key: &str) -> anyhow::Result<()> {
+    let statement = Query::update()
+        .table(evento::sql::Subscriber::Table)
+        .values([
+            (
+                evento::sql::Subscriber::Cursor,
+                Option::<String>::None.into(),
+            ),
+            (evento::sql::Subscriber::Lag, 0.into()),
+        ])
+        .and_where(Expr::col(evento::sql::Subscriber::Key).eq(key))
+        .to_owned();
+
+    QueryPool::connect(dsn).await?.execute(&statement).await?;
+
+    Ok(())
+}
+
+pub async fn rebuild(config: Serve, key: &str) -> anyhow::Result<()> {
+    let Some(projection) = Subscription::find_projection(&config.region, key) else {
+        let keys = Subscription::projections()
+            .map(|projection| projection.key(&config.region))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        anyhow::bail!("projection '{key}' not found, available: {keys}");
+    };
+
+    let executor = crate::connect_evento(&config.dsn).await?;
+    let query_db = QueryPool::connect(&config.query_dsn()).await?;
+    let (errors, errors_receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    let subscription = projection
+        .subscribe::<evento::Evento>(&config.region, errors)?
+        .data(query_db.clone());
+
+    // Taking over the subscriber first stops a running `serve` from acknowledging events
+    // while the projection is emptied, it must be restarted once the rebuild is done.
+    subscription.init(&executor).await?;
+    reset_subscriber_cursor(&config.dsn, key).await?;
+    projection.reset(&query_db).await?;
+    clear_error(&query_db, key).await?;
+
+    let positions = read_positions(&executor, projection, &config.region).await?;
+    let total = positions.len();
+    println!("{key}: replaying {total} events");
+
+    tokio::spawn(record_errors(query_db.clone(), errors_receiver));
+
+    subscription.run(&executor).await?;
+
+    let mut interval = tokio::time::interval(Duration::from_millis(500));
+    let mut processed = 0;
+    let mut stalled = 0;
+
+    while processed < total {
+        interval.tick().await;
+
+        let current = match executor.get_subscriber_cursor(key.to_owned()).await? {
+            Some(cursor) => {
+                let cursor = evento::Event::deserialize_cursor(&cursor)?;
+                positions.partition_point(|position| *position <= (cursor.t, cursor.v, cursor.i))
+            }
+            _ => 0,
+        };
+
+        if current == processed {
+            stalled += 1;
+
+            if stalled > 120 {
+                anyhow::bail!("{key}: stalled at {processed}/{total} events, check logs");
+            }
+
+            continue;
+        }
+
+        stalled = 0;
+        processed = current;
+        println!("{key}: {processed}/{total} events");
+    }
+
+    println!("{key}: rebuilt, restart timada serve to resume live updates");
+
+    Ok(())
+}
+
+pub async fn record_errors(pool: QueryPool, mut receiver: UnboundedReceiver<SubscriptionError>) {
+    while let Some(error) = receiver.recv().await {
+        let statement = Query::insert()
+            .into_table(SubscriptionErrorIden::Table)
+            .columns([
+                SubscriptionErrorIden::Key,
+                SubscriptionErrorIden::Error,
+                SubscriptionErrorIden::CreatedAt,
+            ])
+            .values_panic([
+                error.key.into(),
+                error.error.into(),
+                error.created_at.into(),
+            ])
+            .on_conflict(
+                OnConflict::column(SubscriptionErrorIden::Key)
+                    .update_columns([
+                        SubscriptionErrorIden::Error,
+                        SubscriptionErrorIden::CreatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .to_owned();
+
+        if let Err(err) = pool.execute(&statement).await {
+            tracing::error!("record_errors '{err}'");
+        }
+    }
+}
+
+async fn clear_error(pool: &QueryPool, key: &str) -> anyhow::Result<()> {
+    let statement = Query::delete()
+        .from_table(SubscriptionErrorIden::Table)
+        .and_where(Expr::col(SubscriptionErrorIden::Key).eq(key))
+        .to_owned();
+
+    pool.execute(&statement).await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub id: Ulid,
+    pub timestamp: i64,
+}
+
+impl Position {
+    pub fn timestamp_utc(&self) -> String {
+        timada_market::price::format_timestamp(self.timestamp)
+    }
+}
+
+/// Lag is counted on one page of events at most, a subscription further behind shows `1000+`.
+const MAX_LAG: u16 = 1000;
+
+/// Events a subscription has not handled yet.
+#[derive(Debug, Clone, Copy)]
+pub struct Lag {
+    pub events: usize,
+    pub more: bool,
+}
+
+impl std::fmt::Display for Lag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.more {
+            true => f.pad(&format!("{}+", self.events)),
+            _ => f.pad(&self.events.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SubscriptionStatus {
+    pub key: String,
+    pub cursor: Option<Position>,
+    pub head: Option<Position>,
+    pub lag: Lag,
+    pub error: Option<SubscriptionError>,
+}
+
+pub async fn query_statuses<E: Executor>(
+    executor: &E,
+    pool: &QueryPool,
+    region: &str,
+) -> anyhow::Result<Vec<SubscriptionStatus>> {
+    let statement = Query::select()
+        .columns([
+            SubscriptionErrorIden::Key,
+            SubscriptionErrorIden::Error,
+            SubscriptionErrorIden::CreatedAt,
+        ])
+        .from(SubscriptionErrorIden::Table)
+        .to_owned();
+
+    let mut errors = pool
+        .fetch_all::<SubscriptionError, _>(&statement)
+        .await?
+        .into_iter()
+        .map(|error| (error.key.to_owned(), error))
+        .collect::<HashMap<_, _>>();
+
+    let mut statuses = vec![];
+
+    for subscription in Subscription::all() {
+        let key = subscription.key(region);
+        let routing_key = RoutingKey::Value(Some(region.to_owned()));
+
+        let head = executor
+            .read(
+                subscription.aggregator_types(),
+                routing_key.clone(),
+                Args::backward(1, None),
+            )
+            .await?
+            .edges
+            .last()
+            .map(|edge| Position {
+                id: edge.node.id,
+                timestamp: edge.node.timestamp,
+            });
+
+        let cursor = executor.get_subscriber_cursor(key.to_owned()).await?;
+        let events = executor
+            .read(
+                subscription.aggregator_types(),
+                routing_key.clone(),
+                Args::forward(MAX_LAG, cursor.clone()),
+            )
+            .await?;
+
+        let lag = Lag {
+            events: events.edges.len(),
+            more: events.page_info.has_next_page,
+        };
+
+        let cursor = match cursor {
+            Some(cursor) => {
+                let cursor = evento::Event::deserialize_cursor(&cursor)?;
+
+                Some(Position {
+                    id: cursor.i,
+                    timestamp: cursor.t,
+                })
+            }
+            _ => None,
+        };
+
+        statuses.push(SubscriptionStatus {
+            error: errors.remove(&key),
+            key,
+            cursor,
+            head,
+            lag,
+        });
+    }
+
+    Ok(statuses)
+}
+
+pub async fn status(config: Serve) -> anyhow::Result<()> {
+    let executor = crate::connect_evento(&config.dsn).await?;
+    let query_db = QueryPool::connect(&config.query_dsn()).await?;
+
+    let statuses = query_statuses(&executor, &query_db, &config.region).await?;
+
+    println!(
+        "{:<45} {:<26} {:<26} {:>6}  LAST ERROR",
+        "KEY", "CURSOR", "HEAD", "LAG"
+    );
+
+    for status in statuses {
+        let cursor = status
+            .cursor
+            .map(|position| position.id.to_string())
+            .unwrap_or_else(|| "-".to_owned());
+
+        let head = status
+            .head
+            .map(|position| position.id.to_string())
+            .unwrap_or_else(|| "-".to_owned());
+
+        let error = status
+            .error
+            .map(|error| format!("{} {}", error.created_at_utc(), error.error))
+            .unwrap_or_else(|| "-".to_owned());
+
+        println!(
+            "{:<45} {cursor:<26} {head:<26} {:>6}  {error}",
+            status.key, status.lag
+        );
+    }
+
+    Ok(())
+}
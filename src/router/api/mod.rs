@@ -0,0 +1,86 @@
+pub mod product;
+
+use axum::Json;
+use serde::Serialize;
+use utoipa::{
+    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi, ToSchema,
+};
+
+/// Cursor page of a `ReadResult`, `page_info` reports both directions.
+#[derive(Serialize, ToSchema)]
+pub struct Page<T> {
+    pub edges: Vec<Edge<T>>,
+    pub page_info: PageInfo,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Edge<T> {
+    pub cursor: String,
+    pub node: T,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PageInfo {
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
+    pub start_cursor: Option<String>,
+    pub end_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn new(
+        result: evento::cursor::ReadResult<T>,
+        previous: Option<String>,
+        next: Option<String>,
+    ) -> Self {
+        let edges = result
+            .edges
+            .into_iter()
+            .map(|edge| Edge {
+                cursor: edge.cursor.to_string(),
+                node: edge.node,
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            page_info: PageInfo {
+                has_previous_page: previous.is_some(),
+                has_next_page: next.is_some(),
+                start_cursor: edges.first().map(|edge| edge.cursor.to_owned()),
+                end_cursor: edges.last().map(|edge| edge.cursor.to_owned()),
+            },
+            edges,
+        }
+    }
+}
+
+/// OpenAPI 3.1 document of the JSON API, served at `/api/openapi.json`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Timada API"),
+    paths(product::list, product::get, product::create, product::status),
+    modifiers(&BearerSecurity),
+    security(("bearer" = [])),
+    tags((name = "products", description = "Products of the owner of the authenticated user")),
+)]
+pub struct ApiDoc;
+
+/// API tokens are created on `/tokens`.
+struct BearerSecurity;
+
+impl Modify for BearerSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+    }
+}
+
+pub async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
@@ -0,0 +1,210 @@
+use axum::{
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        Path, Query, State,
+    },
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use timada_identity::user::Permission;
+use timada_market::{
+    price::ScheduledPrice,
+    product::{CreateInput, Product, ProductState, QueryProduct},
+    variant::Variant,
+};
+use timada_shared::{Money, RequestMetadata};
+use utoipa::ToSchema;
+
+use super::Page;
+use crate::{
+    axum_extra::CurrentUser,
+    error::{AppError, ErrorBody},
+    router::market::{load_product, page_cursors, IndexQuery},
+};
+
+#[derive(Serialize, ToSchema)]
+pub struct ProductResponse {
+    pub id: String,
+    #[serde(flatten)]
+    pub product: ProductBody,
+}
+
+/// Fields of the `Product` aggregate returned by the API.
+#[derive(Serialize, ToSchema)]
+pub struct ProductBody {
+    pub name: String,
+    pub state: ProductState,
+    pub failed_reason: String,
+    pub category_ids: Vec<String>,
+    pub variants: Vec<Variant>,
+    pub price: Option<Money>,
+    pub scheduled_prices: Vec<ScheduledPrice>,
+    pub owner_id: Option<String>,
+}
+
+impl From<Product> for ProductBody {
+    fn from(product: Product) -> Self {
+        Self {
+            name: product.name,
+            state: product.state,
+            failed_reason: product.failed_reason,
+            category_ids: product.category_ids,
+            variants: product.variants,
+            price: product.price,
+            scheduled_prices: product.scheduled_prices,
+            owner_id: product.owner_id,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct StatusResponse {
+    pub id: String,
+    pub state: ProductState,
+    pub failed_reason: String,
+}
+
+/// Same filters and cursors as `/market`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/products",
+    tag = "products",
+    params(
+        IndexQuery,
+        ("first" = Option<u16>, Query, description = "Page size, forward"),
+        ("after" = Option<String>, Query, description = "`end_cursor` of the previous page"),
+        ("last" = Option<u16>, Query, description = "Page size, backward"),
+        ("before" = Option<String>, Query, description = "`start_cursor` of the next page"),
+    ),
+    responses(
+        (status = 200, body = Page<QueryProduct>),
+        (status = 401, body = ErrorBody),
+        (status = 403, body = ErrorBody),
+        (status = 422, body = ErrorBody),
+    ),
+)]
+pub async fn list(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    query: Result<Query<IndexQuery>, QueryRejection>,
+) -> Result<Json<Page<QueryProduct>>, AppError> {
+    user.require(Permission::MarketProductRead)?;
+
+    let Query(query) = query?;
+    let input = query.input().map_err(|err| AppError::Validation {
+        message: err.to_string(),
+        fields: Default::default(),
+    })?;
+    let args = input.args.clone();
+    let products = timada_market::product::query_products(
+        &state.query_pool,
+        metadata.user_owner_id.as_deref(),
+        input,
+    )
+    .await?;
+    let (previous, next) = page_cursors(&args, &products);
+
+    Ok(Json(Page::new(products, previous, next)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/products/{id}",
+    tag = "products",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, body = ProductResponse),
+        (status = 401, body = ErrorBody),
+        (status = 403, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+)]
+pub async fn get(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+) -> Result<Json<ProductResponse>, AppError> {
+    user.require(Permission::MarketProductRead)?;
+
+    let product = load_product(&state, &metadata, &id).await?;
+
+    Ok(Json(ProductResponse {
+        id,
+        product: product.item.into(),
+    }))
+}
+
+/// Products are checked asynchronously, the status url tells when it is ready.
+#[utoipa::path(
+    post,
+    path = "/api/v1/products",
+    tag = "products",
+    request_body = CreateInput,
+    responses(
+        (status = 202, body = StatusResponse, headers(("location" = String))),
+        (status = 401, body = ErrorBody),
+        (status = 403, body = ErrorBody),
+        (status = 422, body = ErrorBody),
+    ),
+)]
+pub async fn create(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    input: Result<Json<CreateInput>, JsonRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    user.require(Permission::MarketProductCreate)?;
+
+    let Json(input) = input?;
+    let id = timada_market::product::create(input)?
+        .metadata(&metadata)?
+        .routing_key(state.config.region)
+        .commit(&state.evento)
+        .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        [(
+            header::LOCATION,
+            crate::router::api_v1_products_status(Some(id.to_owned())),
+        )],
+        Json(StatusResponse {
+            id,
+            state: ProductState::Checking,
+            failed_reason: "".to_owned(),
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/products/{id}/status",
+    tag = "products",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, body = StatusResponse),
+        (status = 401, body = ErrorBody),
+        (status = 403, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+)]
+pub async fn status(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+) -> Result<Json<StatusResponse>, AppError> {
+    user.require(Permission::MarketProductRead)?;
+
+    let product = load_product(&state, &metadata, &id).await?;
+
+    Ok(Json(StatusResponse {
+        id,
+        state: product.item.state,
+        failed_reason: product.item.failed_reason,
+    }))
+}
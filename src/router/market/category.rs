@@ -0,0 +1,133 @@
+use crate::{
+    axum_extra::{CurrentUser, Template},
+    filters,
+};
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Redirect},
+    Form,
+};
+use timada_identity::user::Permission;
+use timada_market::category::{Category, CreateInput, MoveInput, QueryCategory, RenameInput};
+use timada_shared::{NotFound, RequestMetadata};
+
+#[derive(askama::Template)]
+#[template(path = "market/categories.html")]
+pub struct IndexTemplate {
+    pub categories: evento::cursor::ReadResult<QueryCategory>,
+}
+
+pub async fn index(
+    html: Template<IndexTemplate>,
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketCategoryRead)?;
+
+    let categories = timada_market::category::query_categories(
+        &state.query_pool,
+        metadata.user_owner_id.as_deref(),
+    )
+    .await?;
+
+    Ok(html.template(IndexTemplate { categories }))
+}
+
+pub async fn create(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Form(input): Form<CreateInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketCategoryManage)?;
+
+    if let Some(parent_id) = input.parent_id.as_deref().filter(|id| !id.is_empty()) {
+        load_category(&state, &metadata, parent_id).await?;
+    }
+
+    timada_market::category::create(&state.evento, input)
+        .await?
+        .metadata(&metadata)?
+        .routing_key(state.config.region)
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::MARKET_S_CATEGORIES))
+}
+
+pub async fn rename(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<RenameInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketCategoryManage)?;
+
+    load_category(&state, &metadata, &id).await?;
+
+    timada_market::category::rename(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::MARKET_S_CATEGORIES))
+}
+
+pub async fn move_to(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<MoveInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketCategoryManage)?;
+
+    load_category(&state, &metadata, &id).await?;
+
+    if let Some(parent_id) = input.parent_id.as_deref().filter(|id| !id.is_empty()) {
+        load_category(&state, &metadata, parent_id).await?;
+    }
+
+    timada_market::category::move_to(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::MARKET_S_CATEGORIES))
+}
+
+pub async fn delete(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketCategoryManage)?;
+
+    load_category(&state, &metadata, &id).await?;
+
+    timada_market::category::delete(&state.evento, id)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::MARKET_S_CATEGORIES))
+}
+
+async fn load_category(
+    state: &crate::State,
+    metadata: &RequestMetadata,
+    id: &str,
+) -> anyhow::Result<evento::LoadResult<Category>> {
+    let category = evento::load::<Category, _>(&state.evento, id).await?;
+    if category.item.owner_id != metadata.user_owner_id {
+        anyhow::bail!(NotFound::new("category not found"));
+    }
+
+    Ok(category)
+}
@@ -1,37 +1,264 @@
-use crate::{axum_extra::Template, filters};
+pub mod category;
+
+use crate::{
+    axum_extra::{CurrentUser, Template},
+    filters,
+};
 use axum::{
-    extract::{Path, State},
-    response::IntoResponse,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Redirect},
     Form,
 };
-use timada_market::product::{CreateInput, Product, ProductState, QueryProduct};
-use timada_shared::RequestMetadata;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, str::FromStr};
+use timada_identity::user::Permission;
+use timada_inventory::stock::{
+    AdjustInput, QueryStock, QueryStockTotal, ReceiveInput, ReserveInput,
+};
+use timada_market::{
+    category::QueryCategory,
+    price::QueryPriceHistory,
+    product::{
+        AddVariantsInput, AssignCategoryInput, ChangePriceInput, CreateInput, Product,
+        ProductState, QueryProduct, QueryProductsFilter, QueryProductsInput, QueryProductsSort,
+        QueryProductsSortKey, RemoveVariantInput, SchedulePriceInput, UnschedulePriceInput,
+        UpdateInput,
+    },
+};
+use timada_shared::{NotFound, RequestMetadata};
+use utoipa::IntoParams;
 
 #[derive(askama::Template)]
 #[template(path = "market/index.html")]
 pub struct IndexTemplate {
+    pub archived: bool,
+    pub query: IndexQuery,
+    pub query_string: String,
+    pub categories: evento::cursor::ReadResult<QueryCategory>,
+    pub previous: Option<String>,
+    pub next: Option<String>,
     pub log: Option<(String, ProductState, String)>,
     pub products: evento::cursor::ReadResult<QueryProduct>,
+    pub stocks: HashMap<String, QueryStockTotal>,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IndexQuery {
+    /// Full text search on the name.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub q: String,
+    /// One of `checking`, `failed`, `ready` or `archived`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub state: String,
+    /// Created on or after, `YYYY-MM-DD`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub from: String,
+    /// Created on or before, `YYYY-MM-DD`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub to: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub prefix: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub category: String,
+    /// One of `name`, `created-at` or `price`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub sort: String,
+    /// `asc` or `desc`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub order: String,
+    #[serde(skip_serializing)]
+    pub first: Option<u16>,
+    #[serde(skip_serializing)]
+    pub after: Option<String>,
+    #[serde(skip_serializing)]
+    pub last: Option<u16>,
+    #[serde(skip_serializing)]
+    pub before: Option<String>,
+}
+
+impl IndexQuery {
+    fn args(&self) -> evento::cursor::Args {
+        evento::cursor::Args {
+            first: self.first,
+            after: self.after.to_owned().map(Into::into),
+            last: self.last,
+            before: self.before.to_owned().map(Into::into),
+        }
+    }
+
+    pub fn input(&self) -> anyhow::Result<QueryProductsInput> {
+        let date = |value: &str| -> anyhow::Result<Option<NaiveDate>> {
+            if value.is_empty() {
+                return Ok(None);
+            }
+
+            Ok(Some(NaiveDate::parse_from_str(value, "%Y-%m-%d")?))
+        };
+
+        let state = match self.state.as_str() {
+            "" => None,
+            state => Some(ProductState::from_str(state)?),
+        };
+
+        let key = match self.sort.as_str() {
+            "" => QueryProductsSortKey::default(),
+            key => QueryProductsSortKey::from_str(key)?,
+        };
+
+        Ok(QueryProductsInput {
+            filter: QueryProductsFilter {
+                state,
+                include_archived: false,
+                created_from: date(&self.from)?,
+                created_to: date(&self.to)?,
+                name_prefix: Some(self.prefix.to_owned()),
+                category_id: Some(self.category.to_owned()),
+            },
+            search: Some(self.q.to_owned()),
+            sort: QueryProductsSort {
+                key,
+                desc: self.order == "desc",
+            },
+            args: self.args(),
+        })
+    }
+}
+
+/// evento only reports the page_info of the requested direction, the other one is known from
+/// the cursor we came from.
+pub fn page_cursors(
+    args: &evento::cursor::Args,
+    products: &evento::cursor::ReadResult<QueryProduct>,
+) -> (Option<String>, Option<String>) {
+    let backward = args.is_backward();
+    let has_previous = if backward {
+        products.page_info.has_previous_page
+    } else {
+        args.after.is_some()
+    };
+    let has_next = if backward {
+        args.before.is_some()
+    } else {
+        products.page_info.has_next_page
+    };
+
+    let previous = products
+        .edges
+        .first()
+        .filter(|_| has_previous)
+        .map(|edge| edge.cursor.to_string());
+    let next = products
+        .edges
+        .last()
+        .filter(|_| has_next)
+        .map(|edge| edge.cursor.to_string());
+
+    (previous, next)
+}
+
+async fn list(
+    state: &crate::State,
+    metadata: &RequestMetadata,
+    query: IndexQuery,
+    archived: bool,
+) -> anyhow::Result<IndexTemplate> {
+    let mut input = query.input()?;
+    if archived {
+        input.filter.state = Some(ProductState::Archived);
+    }
+
+    let args = input.args.clone();
+    let products = timada_market::product::query_products(
+        &state.query_pool,
+        metadata.user_owner_id.as_deref(),
+        input,
+    )
+    .await?;
+    let stocks = query_stock_totals(state, &products).await?;
+    let categories = timada_market::category::query_categories(
+        &state.query_pool,
+        metadata.user_owner_id.as_deref(),
+    )
+    .await?;
+    let (previous, next) = page_cursors(&args, &products);
+    let query_string = serde_urlencoded::to_string(&query)?;
+
+    Ok(IndexTemplate {
+        archived,
+        query,
+        query_string,
+        categories,
+        previous,
+        next,
+        log: None,
+        products,
+        stocks,
+    })
 }
 
 pub async fn index(
     html: Template<IndexTemplate>,
     State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Query(query): Query<IndexQuery>,
 ) -> Result<impl IntoResponse, crate::error::AppError> {
-    let products = timada_market::product::query_products(&state.query_pool).await?;
-    Ok(html.template(IndexTemplate {
-        log: None,
-        products,
-    }))
+    user.require(Permission::MarketProductRead)?;
+
+    Ok(html.template(list(&state, &metadata, query, false).await?))
+}
+
+pub async fn archived(
+    html: Template<IndexTemplate>,
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Query(query): Query<IndexQuery>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductRead)?;
+
+    Ok(html.template(list(&state, &metadata, query, true).await?))
+}
+
+/// Products of another owner are reported as missing.
+pub async fn load_product(
+    state: &crate::State,
+    metadata: &RequestMetadata,
+    id: &str,
+) -> anyhow::Result<evento::LoadResult<Product>> {
+    let product = evento::load::<Product, _>(&state.evento, id).await?;
+    if product.item.owner_id != metadata.user_owner_id {
+        anyhow::bail!(NotFound::new("product not found"));
+    }
+
+    Ok(product)
+}
+
+async fn query_stock_totals(
+    state: &crate::State,
+    products: &evento::cursor::ReadResult<QueryProduct>,
+) -> anyhow::Result<HashMap<String, QueryStockTotal>> {
+    let product_ids = products
+        .edges
+        .iter()
+        .map(|edge| edge.node.id.to_owned())
+        .collect();
+
+    timada_inventory::stock::query_stock_totals(&state.query_pool, product_ids).await
 }
 
 #[axum::debug_handler]
 pub async fn create(
     html: Template<IndexTemplate>,
     State(state): State<crate::State>,
+    user: CurrentUser,
     metadata: RequestMetadata,
     Form(input): Form<CreateInput>,
 ) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductCreate)?;
+
     let id = timada_market::product::create(input)?
         .metadata(&metadata)?
         .routing_key(state.config.region)
@@ -39,20 +266,358 @@ pub async fn create(
         .await?;
 
     Ok(html.template(IndexTemplate {
+        archived: false,
+        query: Default::default(),
+        query_string: "".to_owned(),
+        categories: Default::default(),
+        previous: None,
+        next: None,
         log: Some((id, ProductState::Checking, "".to_owned())),
         products: Default::default(),
+        stocks: Default::default(),
     }))
 }
 
 pub async fn status(
     html: Template<IndexTemplate>,
     State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
     Path((id,)): Path<(String,)>,
 ) -> Result<impl IntoResponse, crate::error::AppError> {
-    let product = evento::load::<Product, _>(&state.evento, &id).await?;
+    user.require(Permission::MarketProductRead)?;
+
+    let product = load_product(&state, &metadata, &id).await?;
 
     Ok(html.template(IndexTemplate {
+        archived: false,
+        query: Default::default(),
+        query_string: "".to_owned(),
+        categories: Default::default(),
+        previous: None,
+        next: None,
         log: Some((id, product.item.state, product.item.failed_reason)),
         products: Default::default(),
+        stocks: Default::default(),
+    }))
+}
+
+#[derive(askama::Template)]
+#[template(path = "market/edit.html")]
+pub struct EditTemplate {
+    pub id: String,
+    pub product: Product,
+    pub categories: evento::cursor::ReadResult<QueryCategory>,
+    pub price_history: evento::cursor::ReadResult<QueryPriceHistory>,
+    pub stocks: evento::cursor::ReadResult<QueryStock>,
+    pub currency: String,
+}
+
+pub async fn edit(
+    html: Template<EditTemplate>,
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductRead)?;
+
+    let product = load_product(&state, &metadata, &id).await?;
+    let categories = timada_market::category::query_categories(
+        &state.query_pool,
+        metadata.user_owner_id.as_deref(),
+    )
+    .await?;
+    let stocks = timada_inventory::stock::query_stocks(&state.query_pool, &id).await?;
+    let price_history = timada_market::price::query_price_history(&state.query_pool, &id).await?;
+    let currency = match &product.item.price {
+        Some(price) => price.currency.to_owned(),
+        _ => timada_shared::region_currency(&state.config.region).to_owned(),
+    };
+
+    Ok(html.template(EditTemplate {
+        id,
+        product: product.item,
+        categories,
+        price_history,
+        stocks,
+        currency,
     }))
 }
+
+pub async fn update(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<UpdateInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    timada_market::product::update(&state.evento, &state.query_pool, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::MARKET))
+}
+
+pub async fn archive(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductArchive)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    timada_market::product::archive(&state.evento, id)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::MARKET))
+}
+
+pub async fn restore(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductArchive)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    timada_market::product::restore(&state.evento, id)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::MARKET_S_ARCHIVED))
+}
+
+pub async fn delete(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductDelete)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    timada_market::product::delete(&state.evento, id)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::MARKET_S_ARCHIVED))
+}
+
+pub async fn assign_category(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<AssignCategoryInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_market::product::assign_category(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn unassign_category(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<AssignCategoryInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_market::product::unassign_category(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn add_variants(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<AddVariantsInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_market::product::add_variants(&state.evento, &state.query_pool, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn remove_variant(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<RemoveVariantInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_market::product::remove_variant(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn change_price(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<ChangePriceInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_market::product::change_price(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn schedule_price(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<SchedulePriceInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_market::product::schedule_price(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn unschedule_price(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<UnschedulePriceInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::MarketProductUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_market::product::unschedule_price(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn receive_stock(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<ReceiveInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::InventoryStockUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_inventory::stock::receive(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn adjust_stock(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<AdjustInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::InventoryStockUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_inventory::stock::adjust(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
+
+pub async fn reserve_stock(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<ReserveInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::InventoryStockUpdate)?;
+
+    load_product(&state, &metadata, &id).await?;
+
+    let id = timada_inventory::stock::reserve(&state.evento, id, input)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(&crate::router::market_s_edit(Some(id))))
+}
@@ -1,12 +1,23 @@
+pub mod api;
 mod market;
+mod projection;
+mod session;
+mod token;
 
 use axum::{
+    middleware,
     response::IntoResponse,
     routing::{get, post},
     Router,
 };
+use utoipa::OpenApi;
+use utoipa_scalar::{Scalar, Servable};
 
-use crate::{assets, axum_extra::Template, filters};
+use crate::{
+    assets,
+    axum_extra::{CurrentUser, Template},
+    filters,
+};
 
 #[derive(askama::Template)]
 #[template(path = "index.html")]
@@ -16,17 +27,124 @@ async fn index(template: Template<IndexTemplate>) -> impl IntoResponse {
     template.template(IndexTemplate)
 }
 
-pub fn create_router() -> Router<crate::State> {
+pub fn create_router(state: &crate::State) -> Router<crate::State> {
     Router::new()
         .fallback(get(assets::static_handler))
         .route("/", get(index))
+        .route(LOGIN, get(session::index).post(session::login))
+        .route(LOGIN_S_OIDC, get(session::oidc))
+        .route(LOGIN_S_OIDC_CALLBACK, get(session::oidc_callback))
+        .route(LOGOUT, post(session::logout))
+        .route(API_OPENAPI, get(api::openapi))
+        .merge(Scalar::with_url(API_DOCS, api::ApiDoc::openapi()))
+        .merge(
+            market_router()
+                .merge(token_router())
+                .route(PROJECTIONS, get(projection::index))
+                .merge(api_router())
+                .route_layer(middleware::from_extractor_with_state::<CurrentUser, _>(
+                    state.clone(),
+                )),
+        )
+        .layer(middleware::from_fn(crate::error::render))
+}
+
+/// Versioned JSON API, authenticated like the HTML routes.
+fn api_router() -> Router<crate::State> {
+    Router::new()
+        .route(
+            API_V1_PRODUCTS,
+            get(api::product::list).post(api::product::create),
+        )
+        .route(&api_v1_products_get(None), get(api::product::get))
+        .route(&api_v1_products_status(None), get(api::product::status))
+}
+
+/// API tokens of the logged in user.
+fn token_router() -> Router<crate::State> {
+    Router::new()
+        .route(TOKENS, get(token::index))
+        .route(TOKENS_S_CREATE, post(token::create))
+        .route(&tokens_s_revoke(None), post(token::revoke))
+}
+
+/// Every route under `/market`, only served to logged in users and API tokens.
+fn market_router() -> Router<crate::State> {
+    Router::new()
         .route(MARKET, get(market::index))
+        .route(MARKET_S_ARCHIVED, get(market::archived))
         .route(MARKET_S_CREATE, post(market::create))
         .route(&market_s_create_status(None), get(market::status))
+        .route(&market_s_edit(None), get(market::edit).post(market::update))
+        .route(&market_s_archive(None), post(market::archive))
+        .route(&market_s_restore(None), post(market::restore))
+        .route(&market_s_delete(None), post(market::delete))
+        .route(
+            &market_s_assign_category(None),
+            post(market::assign_category),
+        )
+        .route(
+            &market_s_unassign_category(None),
+            post(market::unassign_category),
+        )
+        .route(&market_s_add_variants(None), post(market::add_variants))
+        .route(&market_s_remove_variant(None), post(market::remove_variant))
+        .route(&market_s_change_price(None), post(market::change_price))
+        .route(&market_s_schedule_price(None), post(market::schedule_price))
+        .route(
+            &market_s_unschedule_price(None),
+            post(market::unschedule_price),
+        )
+        .route(&market_s_receive_stock(None), post(market::receive_stock))
+        .route(&market_s_adjust_stock(None), post(market::adjust_stock))
+        .route(&market_s_reserve_stock(None), post(market::reserve_stock))
+        .route(MARKET_S_CATEGORIES, get(market::category::index))
+        .route(MARKET_S_CATEGORIES_CREATE, post(market::category::create))
+        .route(
+            &market_s_categories_rename(None),
+            post(market::category::rename),
+        )
+        .route(
+            &market_s_categories_move(None),
+            post(market::category::move_to),
+        )
+        .route(
+            &market_s_categories_delete(None),
+            post(market::category::delete),
+        )
 }
 
+pub const LOGIN: &str = "/login";
+pub const LOGIN_S_OIDC: &str = "/login/-/oidc";
+pub const LOGIN_S_OIDC_CALLBACK: &str = "/login/-/oidc/callback";
+pub const LOGOUT: &str = "/logout";
+pub const PROJECTIONS: &str = "/projections";
+pub const TOKENS: &str = "/tokens";
+pub const TOKENS_S_CREATE: &str = "/tokens/-/create";
+pub const API: &str = "/api/";
+pub const API_OPENAPI: &str = "/api/openapi.json";
+pub const API_DOCS: &str = "/api/docs";
+pub const API_V1_PRODUCTS: &str = "/api/v1/products";
 pub const MARKET: &str = "/market";
 pub const MARKET_S_CREATE: &str = "/market/-/create";
+pub const MARKET_S_ARCHIVED: &str = "/market/-/archived";
+pub const MARKET_S_CATEGORIES: &str = "/market/-/categories";
+pub const MARKET_S_CATEGORIES_CREATE: &str = "/market/-/categories/create";
+
+pub fn tokens_s_revoke(id: Option<String>) -> String {
+    format!("/tokens/-/revoke/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn api_v1_products_get(id: Option<String>) -> String {
+    format!("/api/v1/products/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn api_v1_products_status(id: Option<String>) -> String {
+    format!(
+        "/api/v1/products/{}/status",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
 
 pub fn market_s_create_status(id: Option<String>) -> String {
     format!(
@@ -34,3 +152,101 @@ pub fn market_s_create_status(id: Option<String>) -> String {
         id.unwrap_or("{id}".to_owned())
     )
 }
+
+pub fn market_s_edit(id: Option<String>) -> String {
+    format!("/market/-/edit/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn market_s_archive(id: Option<String>) -> String {
+    format!("/market/-/archive/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn market_s_restore(id: Option<String>) -> String {
+    format!("/market/-/restore/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn market_s_delete(id: Option<String>) -> String {
+    format!("/market/-/delete/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn market_s_assign_category(id: Option<String>) -> String {
+    format!(
+        "/market/-/assign-category/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_unassign_category(id: Option<String>) -> String {
+    format!(
+        "/market/-/unassign-category/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_categories_rename(id: Option<String>) -> String {
+    format!(
+        "/market/-/categories/rename/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_categories_move(id: Option<String>) -> String {
+    format!(
+        "/market/-/categories/move/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_categories_delete(id: Option<String>) -> String {
+    format!(
+        "/market/-/categories/delete/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_add_variants(id: Option<String>) -> String {
+    format!("/market/-/add-variants/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn market_s_remove_variant(id: Option<String>) -> String {
+    format!(
+        "/market/-/remove-variant/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_change_price(id: Option<String>) -> String {
+    format!("/market/-/change-price/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn market_s_schedule_price(id: Option<String>) -> String {
+    format!(
+        "/market/-/schedule-price/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_unschedule_price(id: Option<String>) -> String {
+    format!(
+        "/market/-/unschedule-price/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_receive_stock(id: Option<String>) -> String {
+    format!(
+        "/market/-/receive-stock/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
+
+pub fn market_s_adjust_stock(id: Option<String>) -> String {
+    format!("/market/-/adjust-stock/{}", id.unwrap_or("{id}".to_owned()))
+}
+
+pub fn market_s_reserve_stock(id: Option<String>) -> String {
+    format!(
+        "/market/-/reserve-stock/{}",
+        id.unwrap_or("{id}".to_owned())
+    )
+}
@@ -0,0 +1,27 @@
+use crate::{
+    axum_extra::{CurrentUser, Template},
+    filters,
+    projection::SubscriptionStatus,
+};
+use axum::{extract::State, response::IntoResponse};
+use timada_identity::user::Permission;
+
+#[derive(askama::Template)]
+#[template(path = "projections.html")]
+pub struct IndexTemplate {
+    pub statuses: Vec<SubscriptionStatus>,
+}
+
+pub async fn index(
+    html: Template<IndexTemplate>,
+    State(state): State<crate::State>,
+    user: CurrentUser,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::AdminProjectionRead)?;
+
+    let statuses =
+        crate::projection::query_statuses(&state.evento, &state.query_pool, &state.config.region)
+            .await?;
+
+    Ok(html.template(IndexTemplate { statuses }))
+}
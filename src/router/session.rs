@@ -0,0 +1,176 @@
+use crate::{
+    axum_extra::{Session, Template},
+    filters, oidc,
+};
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Redirect, Response},
+    Form,
+};
+use axum_extra::extract::SignedCookieJar;
+use std::time::Duration;
+use timada_identity::user::{LoginInput, RegisterExternalInput};
+use timada_shared::RequestMetadata;
+use ulid::Ulid;
+
+#[derive(askama::Template)]
+#[template(path = "login.html")]
+pub struct LoginTemplate {
+    pub email: String,
+    pub error: Option<String>,
+    /// Shows the identity provider login link.
+    pub oidc: bool,
+}
+
+pub async fn index(
+    html: Template<LoginTemplate>,
+    State(state): State<crate::State>,
+) -> impl IntoResponse {
+    html.template(LoginTemplate {
+        email: "".to_owned(),
+        error: None,
+        oidc: state.oidc.is_some(),
+    })
+}
+
+pub async fn login(
+    html: Template<LoginTemplate>,
+    State(state): State<crate::State>,
+    jar: SignedCookieJar,
+    Form(input): Form<LoginInput>,
+) -> Response {
+    let email = input.email.to_owned();
+
+    match timada_identity::user::login(&state.evento, &state.query_pool, input).await {
+        Ok(user_id) => (
+            jar.add(Session::cookie(&user_id, state.config.secure_cookie)),
+            Redirect::to(crate::router::MARKET),
+        )
+            .into_response(),
+        Err(err) => html
+            .template(LoginTemplate {
+                email,
+                error: Some(err.to_string()),
+                oidc: state.oidc.is_some(),
+            })
+            .into_response(),
+    }
+}
+
+pub async fn logout(jar: SignedCookieJar) -> impl IntoResponse {
+    (
+        jar.remove(Session::removal_cookie()),
+        Redirect::to(crate::router::LOGIN),
+    )
+}
+
+/// Redirects to the identity provider, the pending authorization is kept in a signed cookie.
+pub async fn oidc(State(state): State<crate::State>, jar: SignedCookieJar) -> Response {
+    let Some(provider) = &state.oidc else {
+        return Redirect::to(crate::router::LOGIN).into_response();
+    };
+
+    let (url, pending) = provider.authorize_url();
+
+    (
+        jar.add(pending.cookie(state.config.secure_cookie)),
+        Redirect::to(&url),
+    )
+        .into_response()
+}
+
+/// Users are matched by email, unknown ones are registered without password with the roles of
+/// the `[oidc]` section.
+pub async fn oidc_callback(
+    html: Template<LoginTemplate>,
+    State(state): State<crate::State>,
+    jar: SignedCookieJar,
+    Query(callback): Query<oidc::Callback>,
+) -> Response {
+    let Some(provider) = &state.oidc else {
+        return Redirect::to(crate::router::LOGIN).into_response();
+    };
+
+    let pending = oidc::Pending::from_jar(&jar);
+    let jar = jar.remove(oidc::Pending::removal_cookie());
+
+    let user_id = match pending {
+        Some(pending) => match provider.claims(pending, callback).await {
+            Ok(claims) => oidc_user_id(&state, provider, claims).await,
+            Err(err) => Err(err),
+        },
+        _ => Err(anyhow::anyhow!("invalid oidc state")),
+    };
+
+    let user_id = match user_id {
+        Ok(user_id) => user_id,
+        Err(err) => {
+            return (
+                jar,
+                html.template(LoginTemplate {
+                    email: "".to_owned(),
+                    error: Some(err.to_string()),
+                    oidc: true,
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        jar.add(Session::cookie(&user_id, state.config.secure_cookie)),
+        Redirect::to(crate::router::MARKET),
+    )
+        .into_response()
+}
+
+/// Users registered by the identity provider are logged in, unknown ones are registered and
+/// waited for until the users projection has them.
+async fn oidc_user_id(
+    state: &crate::State,
+    provider: &oidc::Provider,
+    claims: oidc::Claims,
+) -> anyhow::Result<String> {
+    let email = claims.email.trim().to_lowercase();
+
+    if let Some(id) = timada_identity::user::login_external(
+        &state.evento,
+        &state.query_pool,
+        &email,
+        claims.owner_id.as_deref(),
+    )
+    .await?
+    {
+        return Ok(id);
+    }
+
+    let input = RegisterExternalInput {
+        email,
+        owner_id: claims.owner_id,
+        roles: provider.roles.to_owned(),
+    };
+
+    let id = timada_identity::user::register_external(&state.query_pool, input)
+        .await?
+        .metadata(&RequestMetadata {
+            id: Ulid::new().to_string(),
+            ..Default::default()
+        })?
+        .routing_key(state.config.region.to_owned())
+        .commit(&state.evento)
+        .await?;
+
+    // The session is rejected until the users projection has caught up.
+    for _ in 0..50 {
+        if timada_identity::user::find_user(&state.query_pool, &id)
+            .await?
+            .is_some()
+        {
+            return Ok(id);
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    anyhow::bail!("account is being created, please try again")
+}
@@ -0,0 +1,78 @@
+use crate::{
+    axum_extra::{CurrentUser, Template},
+    filters,
+};
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Redirect},
+    Form,
+};
+use timada_identity::{
+    token::{CreateInput, QueryToken},
+    user::Permission,
+};
+use timada_shared::RequestMetadata;
+
+#[derive(askama::Template)]
+#[template(path = "tokens.html")]
+pub struct IndexTemplate {
+    pub tokens: Vec<QueryToken>,
+    /// Secret of the token just created, it is not shown again.
+    pub secret: Option<String>,
+}
+
+pub async fn index(
+    html: Template<IndexTemplate>,
+    State(state): State<crate::State>,
+    user: CurrentUser,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require(Permission::IdentityTokenManage)?;
+
+    let tokens = timada_identity::token::query_tokens(&state.query_pool, &user.user.id).await?;
+
+    Ok(html.template(IndexTemplate {
+        tokens,
+        secret: None,
+    }))
+}
+
+pub async fn create(
+    html: Template<IndexTemplate>,
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Form(input): Form<CreateInput>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require_session(Permission::IdentityTokenManage)?;
+
+    let (token, secret) = timada_identity::token::create(&user.user.id, input)?;
+    token
+        .metadata(&metadata)?
+        .routing_key(state.config.region)
+        .commit(&state.evento)
+        .await?;
+
+    let tokens = timada_identity::token::query_tokens(&state.query_pool, &user.user.id).await?;
+
+    Ok(html.template(IndexTemplate {
+        tokens,
+        secret: Some(secret),
+    }))
+}
+
+pub async fn revoke(
+    State(state): State<crate::State>,
+    user: CurrentUser,
+    metadata: RequestMetadata,
+    Path((id,)): Path<(String,)>,
+) -> Result<impl IntoResponse, crate::error::AppError> {
+    user.require_session(Permission::IdentityTokenManage)?;
+
+    timada_identity::token::revoke(&state.evento, id, &user.user.id)
+        .await?
+        .metadata(&metadata)?
+        .commit(&state.evento)
+        .await?;
+
+    Ok(Redirect::to(crate::router::TOKENS))
+}
@@ -0,0 +1,63 @@
+use timada_identity::user::{ChangeRolesInput, RegisterInput, Role};
+use timada_shared::{QueryPool, RequestMetadata};
+use ulid::Ulid;
+
+use crate::Serve;
+
+/// Values of the repeated `--role` argument, clap only accepts known roles.
+pub fn roles(matches: &clap::ArgMatches) -> Vec<Role> {
+    matches
+        .get_many::<String>("role")
+        .unwrap_or_default()
+        .filter_map(|role| role.parse().ok())
+        .collect()
+}
+
+fn metadata() -> RequestMetadata {
+    RequestMetadata {
+        id: Ulid::new().to_string(),
+        ..Default::default()
+    }
+}
+
+/// The email is checked against the users projection, `timada serve` has to have caught up with
+/// previous registrations.
+pub async fn create(config: Serve, input: RegisterInput) -> anyhow::Result<()> {
+    let executor = crate::connect_evento(&config.dsn).await?;
+    let query_db = QueryPool::connect(&config.query_dsn()).await?;
+
+    let id = timada_identity::user::register(&query_db, input)
+        .await?
+        .metadata(&metadata())?
+        .routing_key(config.region)
+        .commit(&executor)
+        .await?;
+
+    println!("user {id} created");
+
+    Ok(())
+}
+
+pub async fn change_roles(
+    config: Serve,
+    email: &str,
+    input: ChangeRolesInput,
+) -> anyhow::Result<()> {
+    let executor = crate::connect_evento(&config.dsn).await?;
+    let query_db = QueryPool::connect(&config.query_dsn()).await?;
+
+    let email = email.trim().to_lowercase();
+    let Some(id) = timada_identity::user::find_user_id_by_email(&query_db, &email).await? else {
+        anyhow::bail!("user '{email}' not found");
+    };
+
+    timada_identity::user::change_roles(&executor, &id, input)
+        .await?
+        .metadata(&metadata())?
+        .commit(&executor)
+        .await?;
+
+    println!("user {id} roles changed");
+
+    Ok(())
+}
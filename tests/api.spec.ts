@@ -0,0 +1,49 @@
+import { test, expect } from './fixtures';
+
+// The API accepts the session of the logged in page like the HTML routes.
+
+test('a product created through the API becomes ready', async ({ page }) => {
+  const name = `api ${Date.now() % 1000000}`;
+  const created = await page.request.post('http://localhost:3000/api/v1/products', {
+    data: { name },
+  });
+
+  expect(created.status()).toBe(202);
+  const { id, state } = await created.json();
+  expect(state).toBe('Checking');
+  expect(created.headers()['location']).toBe(`/api/v1/products/${id}/status`);
+
+  await expect(async () => {
+    const status = await page.request.get(`http://localhost:3000/api/v1/products/${id}/status`);
+    expect((await status.json()).state).toBe('Ready');
+  }).toPass();
+
+  const product = await page.request.get(`http://localhost:3000/api/v1/products/${id}`);
+  expect(product.status()).toBe(200);
+  expect(await product.json()).toMatchObject({ id, name, state: 'Ready' });
+});
+
+test('errors are answered with JSON', async ({ page, request }) => {
+  const invalid = await page.request.post('http://localhost:3000/api/v1/products', {
+    data: { name: 'ab' },
+  });
+  expect(invalid.status()).toBe(422);
+  expect((await invalid.json()).fields).toHaveProperty('name');
+
+  const missing = await page.request.get('http://localhost:3000/api/v1/products/unknown');
+  expect(missing.status()).toBe(404);
+  expect(await missing.json()).toHaveProperty('error');
+
+  const anonymous = await request.get('http://localhost:3000/api/v1/products');
+  expect(anonymous.status()).toBe(401);
+  expect(await anonymous.json()).toHaveProperty('error');
+});
+
+test('products are listed in pages', async ({ page }) => {
+  const response = await page.request.get('http://localhost:3000/api/v1/products?first=1');
+  expect(response.status()).toBe(200);
+
+  const { edges, page_info } = await response.json();
+  expect(edges.length).toBeLessThanOrEqual(1);
+  expect(page_info).toHaveProperty('has_next_page');
+});
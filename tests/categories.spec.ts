@@ -0,0 +1,75 @@
+import { test, expect, type Page } from './fixtures';
+
+async function createCategory(page: Page, name: string, parent?: string) {
+  await page.goto('http://localhost:3000/market/-/categories');
+  const form = page.locator('form', { has: page.getByRole('button', { name: 'Create' }) });
+  await form.locator('input[name="name"]').fill(name);
+  if (parent) {
+    await form.locator('select[name="parent_id"]').selectOption({ label: parent });
+  }
+  await form.getByRole('button', { name: 'Create' }).click();
+}
+
+// Categories are read from the categories projection.
+function category(page: Page, name: string) {
+  return page.locator('.categories > div', {
+    has: page.locator(`input[name="name"][value="${name}"]`),
+  });
+}
+
+test('children of a deleted category move to its parent', async ({ page }) => {
+  const suffix = Date.now() % 1000000;
+  await createCategory(page, `parent ${suffix}`);
+  await expect(async () => {
+    await page.reload();
+    await expect(category(page, `parent ${suffix}`)).toHaveCount(1);
+  }).toPass();
+
+  await createCategory(page, `child ${suffix}`, `parent ${suffix}`);
+  await expect(async () => {
+    await page.reload();
+    await expect(
+      category(page, `child ${suffix}`).locator('select[name="parent_id"] option:checked'),
+    ).toHaveText(`parent ${suffix}`);
+  }).toPass();
+
+  await category(page, `parent ${suffix}`).getByRole('button', { name: 'Delete' }).click();
+
+  await expect(async () => {
+    await page.goto('http://localhost:3000/market/-/categories');
+    await expect(category(page, `parent ${suffix}`)).toHaveCount(0);
+    await expect(
+      category(page, `child ${suffix}`).locator('select[name="parent_id"] option:checked'),
+    ).toHaveText('No parent');
+  }).toPass();
+});
+
+test('a deleted category is removed from its products', async ({ page }) => {
+  const name = `category ${Date.now() % 1000000}`;
+  await createCategory(page, name);
+
+  await page.goto('http://localhost:3000/market');
+  await page.locator('input[name="name"]').fill(name);
+  await page.locator('input[name="name"]').press('Enter');
+  await expect(page.locator('.products')).toContainText(`${name}:ready`);
+  await page
+    .locator('.products > div', { hasText: `${name}:ready` })
+    .getByRole('link', { name: 'Edit' })
+    .click();
+  const url = page.url();
+
+  await expect(async () => {
+    await page.goto(url);
+    await page.locator('select[name="category_id"]').selectOption({ label: name });
+  }).toPass();
+  await page.getByRole('button', { name: 'Add category' }).click();
+  await expect(page.locator('.categories')).toContainText(name);
+
+  await page.goto('http://localhost:3000/market/-/categories');
+  await category(page, name).getByRole('button', { name: 'Delete' }).click();
+
+  await expect(async () => {
+    await page.goto(url);
+    await expect(page.locator('.categories')).not.toContainText(name);
+  }).toPass();
+});
@@ -1,4 +1,4 @@
-import { test, expect } from '@playwright/test';
+import { test, expect } from './fixtures';
 
 test('test', async ({ page }) => {
   await page.goto('http://localhost:3000/market');
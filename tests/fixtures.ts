@@ -0,0 +1,46 @@
+import { test as base, expect, type Page } from '@playwright/test';
+
+export { expect, type Page };
+
+export type User = { email: string; password: string };
+
+// `make e2e.user` creates these users, `support` has the support role and `other` belongs to
+// another owner.
+export const users = {
+  admin: { email: 'admin@timada.localhost', password: 'timada-admin' },
+  support: { email: 'support@timada.localhost', password: 'timada-support' },
+  other: { email: 'other@timada.localhost', password: 'timada-other' },
+} satisfies Record<string, User>;
+
+export async function login(page: Page, user: User) {
+  await page.goto('http://localhost:3000/login');
+  await page.locator('input[name="email"]').fill(user.email);
+  await page.locator('input[name="password"]').fill(user.password);
+  await page.getByRole('button').click();
+  await expect(page).toHaveURL(/\/market$/);
+}
+
+// The page is logged in as `user` before each test, `test.use({ user: users.support })` picks
+// another one.
+export const test = base.extend<{ user: User }>({
+  user: [users.admin, { option: true }],
+  page: async ({ page, user }, use) => {
+    await login(page, user);
+    await use(page);
+  },
+});
+
+export async function createProduct(page: Page, name: string) {
+  await page.goto('http://localhost:3000/market');
+  await page.locator('input[name="name"]').fill(name);
+  await page.locator('input[name="name"]').press('Enter');
+  await expect(page.locator('.products')).toContainText(`${name}:ready`);
+}
+
+export async function editProduct(page: Page, name: string) {
+  await createProduct(page, name);
+  await page
+    .locator('.products > div', { hasText: `${name}:ready` })
+    .getByRole('link', { name: 'Edit' })
+    .click();
+}
@@ -0,0 +1,39 @@
+import { test, expect } from '@playwright/test';
+
+// Needs the `oidc` compose service and the `[oidc]` section of `timada.toml`.
+test.skip(!process.env.TIMADA_OIDC, 'TIMADA_OIDC is not set');
+
+test('login with the identity provider', async ({ page }) => {
+  await page.goto('http://localhost:3000/login');
+  await page.getByRole('link').click();
+  await page.locator('input[name="username"]').fill('oidc-admin');
+  await page.locator('textarea[name="claims"]').fill(
+    JSON.stringify({ email: 'oidc-admin@timada.localhost', email_verified: true }),
+  );
+  await page.getByRole('button').click();
+  await expect(page).toHaveURL(/\/market$/);
+});
+
+test('an unverified email is rejected', async ({ page }) => {
+  await page.goto('http://localhost:3000/login');
+  await page.getByRole('link').click();
+  await page.locator('input[name="username"]').fill('oidc-unverified');
+  await page.locator('textarea[name="claims"]').fill(
+    JSON.stringify({ email: 'admin@timada.localhost' }),
+  );
+  await page.getByRole('button').click();
+  await expect(page).toHaveURL(/\/login\/-\/oidc\/callback/);
+  await expect(page.getByRole('main')).toContainText('email not verified by the identity provider');
+});
+
+test('a password account is not linked to the identity provider', async ({ page }) => {
+  await page.goto('http://localhost:3000/login');
+  await page.getByRole('link').click();
+  await page.locator('input[name="username"]').fill('oidc-password');
+  await page.locator('textarea[name="claims"]').fill(
+    JSON.stringify({ email: 'admin@timada.localhost', email_verified: true }),
+  );
+  await page.getByRole('button').click();
+  await expect(page).toHaveURL(/\/login\/-\/oidc\/callback/);
+  await expect(page.getByRole('main')).toContainText('email already registered with a password');
+});
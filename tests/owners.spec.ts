@@ -0,0 +1,30 @@
+import { test, expect, login, users } from './fixtures';
+
+test('products and categories are only seen by their owner', async ({ browser }) => {
+  const suffix = Date.now() % 1000000;
+  const admin = await browser.newPage();
+  await login(admin, users.admin);
+
+  await admin.locator('input[name="name"]').fill(`owned ${suffix}`);
+  await admin.locator('input[name="name"]').press('Enter');
+  await expect(admin.locator('.products')).toContainText(`owned ${suffix}:ready`);
+  const edit = await admin
+    .locator('.products > div', { hasText: `owned ${suffix}:ready` })
+    .getByRole('link', { name: 'Edit' })
+    .getAttribute('href');
+
+  await admin.goto('http://localhost:3000/market/-/categories');
+  await admin.locator('form input[name="name"]').first().fill(`owned ${suffix}`);
+  await admin.getByRole('button', { name: 'Create' }).click();
+  await expect(admin.locator('.categories')).toContainText(`owned ${suffix}`);
+
+  const other = await browser.newPage();
+  await login(other, users.other);
+  await expect(other.locator('.products')).not.toContainText(`owned ${suffix}`);
+
+  await other.goto(`http://localhost:3000${edit}`);
+  await expect(other.getByRole('main')).toContainText('404 Not Found');
+
+  await other.goto('http://localhost:3000/market/-/categories');
+  await expect(other.getByRole('main')).not.toContainText(`owned ${suffix}`);
+});
@@ -0,0 +1,58 @@
+import { test, expect, editProduct, type Page } from './fixtures';
+
+// Value of a `datetime-local` input, in the time zone of the browser.
+function localDateTime(date: Date) {
+  const pad = (value: number) => String(value).padStart(2, '0');
+  return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}T${pad(date.getHours())}:${pad(date.getMinutes())}`;
+}
+
+async function schedulePrice(page: Page, amount: string, startsAt: Date) {
+  const form = page.locator('form', { has: page.getByRole('button', { name: 'Schedule price' }) });
+  await form.locator('input[name="amount"]').fill(amount);
+  await form.locator('input[name="currency"]').fill('EUR');
+  await form.locator('input[name="starts_at_local"]').fill(localDateTime(startsAt));
+  await form.getByRole('button', { name: 'Schedule price' }).click();
+}
+
+test('a scheduled price is listed until cancelled', async ({ page }) => {
+  await editProduct(page, `schedule ${Date.now() % 1000000}`);
+
+  const startsAt = new Date(Date.now() + 24 * 60 * 60 * 1000);
+  startsAt.setSeconds(0, 0);
+  await schedulePrice(page, '9.99', startsAt);
+
+  const utc = `${startsAt.toISOString().slice(0, 16).replace('T', ' ')} UTC`;
+  await expect(page.locator('.scheduled-prices')).toContainText(`${utc} 9.99 EUR`);
+
+  await page.locator('.scheduled-prices').getByRole('button', { name: 'Cancel' }).click();
+  await expect(page.locator('.scheduled-prices form')).toHaveCount(0);
+});
+
+test('a scheduled date without time zone offset fails', async ({ page }) => {
+  await editProduct(page, `schedule ${Date.now() % 1000000}`);
+
+  const form = page.locator('form', { has: page.getByRole('button', { name: 'Schedule price' }) });
+  await form.locator('input[name="amount"]').fill('9.99');
+  await form
+    .locator('input[name="starts_at"]')
+    .evaluate((input: HTMLInputElement) => (input.value = '2030-01-01T10:00'));
+  await form.getByRole('button', { name: 'Schedule price' }).click();
+
+  await expect(page.getByRole('main')).toContainText('date has no time zone offset');
+});
+
+test('a due scheduled price is applied', async ({ page }) => {
+  // The scheduler runs every 30 seconds.
+  test.setTimeout(180_000);
+
+  await editProduct(page, `schedule ${Date.now() % 1000000}`);
+  await schedulePrice(page, '7.50', new Date(Date.now() + 60 * 1000));
+
+  await expect(async () => {
+    await page.reload();
+    await expect(page.locator('.price-history')).toContainText('7.50 EUR');
+    await expect(page.locator('.price-history')).toContainText('Scheduled');
+  }).toPass({ timeout: 150_000 });
+
+  await expect(page.locator('.scheduled-prices form')).toHaveCount(0);
+});
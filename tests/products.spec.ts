@@ -0,0 +1,27 @@
+import { test, expect, createProduct } from './fixtures';
+
+test('a duplicate product name fails', async ({ page }) => {
+  const name = `dup ${Date.now() % 1000000}`;
+  await createProduct(page, name);
+
+  await page.locator('input[name="name"]').fill(name);
+  await page.locator('input[name="name"]').press('Enter');
+  await expect(page.locator('.log')).toContainText('product name already exists');
+
+  await page.goto(`http://localhost:3000/market?state=failed&prefix=${encodeURIComponent(name)}`);
+  await expect(page.locator('.products')).toContainText(`${name}:failed`);
+});
+
+test('a product cannot be renamed to a taken name', async ({ page }) => {
+  const suffix = Date.now() % 1000000;
+  await createProduct(page, `taken ${suffix}`);
+  await createProduct(page, `rename ${suffix}`);
+
+  await page
+    .locator('.products > div', { hasText: `rename ${suffix}:ready` })
+    .getByRole('link', { name: 'Edit' })
+    .click();
+  await page.locator('input[name="name"]').first().fill(`taken ${suffix}`);
+  await page.getByRole('button', { name: 'Save' }).click();
+  await expect(page.getByRole('main')).toContainText('product name already exists');
+});
@@ -0,0 +1,28 @@
+import { test, expect, users } from './fixtures';
+
+test.use({ user: users.support });
+
+test('support can read products and categories', async ({ page }) => {
+  await expect(page.locator('.products')).toBeVisible();
+
+  await page.goto('http://localhost:3000/market/-/categories');
+  await expect(page.getByRole('main')).not.toContainText('403 Forbidden');
+});
+
+test('support cannot change products or categories', async ({ page }) => {
+  const product = await page.request.post('http://localhost:3000/market/-/create', {
+    form: { name: `support ${Date.now() % 1000000}` },
+  });
+  expect(product.status()).toBe(403);
+
+  const category = await page.request.post('http://localhost:3000/market/-/categories/create', {
+    form: { name: `support ${Date.now() % 1000000}`, parent_id: '' },
+  });
+  expect(category.status()).toBe(403);
+});
+
+test('support cannot read projections', async ({ page }) => {
+  await page.goto('http://localhost:3000/projections');
+  await expect(page.getByRole('main')).toContainText('403 Forbidden');
+  await expect(page.getByRole('main')).toContainText('admin.projection.read');
+});
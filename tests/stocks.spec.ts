@@ -0,0 +1,42 @@
+import { test, expect, editProduct, type Page } from './fixtures';
+
+async function submitStock(page: Page, button: string, quantity: string, reason?: string) {
+  const form = page.locator('form', { has: page.getByRole('button', { name: button }) });
+  await form.locator('input[name="quantity"]').fill(quantity);
+  if (reason) {
+    await form.locator('input[name="reason"]').fill(reason);
+  }
+  await form.getByRole('button', { name: button }).click();
+}
+
+// Stock levels are read from the stocks projection.
+async function expectLevel(page: Page, onHand: string, available: string) {
+  await expect(async () => {
+    await page.reload();
+    const row = page.locator('.stocks tr', { hasText: 'default' });
+    await expect(row.locator('td').nth(2)).toHaveText(onHand);
+    await expect(row.locator('td').nth(3)).toHaveText(available);
+  }).toPass();
+}
+
+test('reserved stock is not available', async ({ page }) => {
+  await editProduct(page, `stock ${Date.now() % 1000000}`);
+  const url = page.url();
+
+  await submitStock(page, 'Receive stock', '10');
+  await expectLevel(page, '10', '10');
+
+  await submitStock(page, 'Reserve stock', '4');
+  await expectLevel(page, '10', '6');
+
+  await submitStock(page, 'Reserve stock', '7');
+  await expect(page.getByRole('main')).toContainText('not enough stock available');
+
+  await page.goto(url);
+  await submitStock(page, 'Adjust stock', '-8', 'damaged');
+  await expect(page.getByRole('main')).toContainText('not enough stock on hand');
+
+  await page.goto(url);
+  await submitStock(page, 'Adjust stock', '-6', 'damaged');
+  await expectLevel(page, '4', '0');
+});
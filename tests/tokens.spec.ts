@@ -0,0 +1,57 @@
+import { test, expect, type Page } from './fixtures';
+
+async function createToken(page: Page, name: string) {
+  await page.goto('http://localhost:3000/tokens');
+  await page.locator('input[name="name"]').fill(name);
+  await page.getByRole('button', { name: 'Create' }).click();
+  await expect(page.getByRole('alert')).toBeVisible();
+
+  return (await page.locator('pre').textContent())?.trim() ?? '';
+}
+
+test('a token authenticates API requests until revoked', async ({ page, request }) => {
+  const name = `token ${Date.now() % 1000000}`;
+  const secret = await createToken(page, name);
+  const headers = { Authorization: `Bearer ${secret}` };
+
+  // Tokens are read from the tokens projection.
+  await expect(async () => {
+    const response = await request.get('http://localhost:3000/api/v1/products', { headers });
+    expect(response.status()).toBe(200);
+  }).toPass();
+
+  await expect(async () => {
+    await page.goto('http://localhost:3000/tokens');
+    await page.getByRole('row', { name }).getByRole('button', { name: 'Revoke' }).click();
+  }).toPass();
+
+  await expect(async () => {
+    const response = await request.get('http://localhost:3000/api/v1/products', { headers });
+    expect(response.status()).toBe(401);
+  }).toPass();
+});
+
+test('a token cannot create tokens', async ({ page, request }) => {
+  const secret = await createToken(page, `token ${Date.now() % 1000000}`);
+  const headers = { Authorization: `Bearer ${secret}` };
+
+  await expect(async () => {
+    const response = await request.get('http://localhost:3000/api/v1/products', { headers });
+    expect(response.status()).toBe(200);
+  }).toPass();
+
+  const response = await request.post('http://localhost:3000/tokens/-/create', {
+    headers,
+    form: { name: 'minted' },
+  });
+  expect(response.status()).toBe(403);
+});
+
+test('an invalid token is refused', async ({ request }) => {
+  const response = await request.get('http://localhost:3000/api/v1/products', {
+    headers: { Authorization: 'Bearer invalid' },
+  });
+
+  expect(response.status()).toBe(401);
+  expect(response.headers()['www-authenticate']).toBe('Bearer');
+});
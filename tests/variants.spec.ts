@@ -0,0 +1,36 @@
+import { test, expect, editProduct } from './fixtures';
+
+test('options generate a variant for each combination', async ({ page }) => {
+  await editProduct(page, `variant ${Date.now() % 1000000}`);
+
+  await page.locator('textarea[name="options"]').fill('size: S, M\ncolour: red');
+  await page.getByRole('button', { name: 'Add variants' }).click();
+
+  await expect(page.locator('.variants > div')).toHaveCount(2);
+  await expect(page.locator('.variants')).toContainText('S / red');
+  await expect(page.locator('.variants')).toContainText('M / red');
+  await expect(page.locator('.variants')).toContainText(/VARIAN-[0-9A-Z]{8}-S-RED/);
+});
+
+test('a removed variant leaves the others', async ({ page }) => {
+  await editProduct(page, `variant ${Date.now() % 1000000}`);
+
+  await page.locator('textarea[name="options"]').fill('size: S, M');
+  await page.getByRole('button', { name: 'Add variants' }).click();
+  await page
+    .locator('.variants > div', { hasText: /-S\s/ })
+    .getByRole('button', { name: 'Remove' })
+    .click();
+
+  await expect(page.locator('.variants > div')).toHaveCount(1);
+  await expect(page.locator('.variants')).toContainText(/VARIAN-[0-9A-Z]{8}-M/);
+});
+
+test('duplicate option values fail', async ({ page }) => {
+  await editProduct(page, `variant ${Date.now() % 1000000}`);
+
+  await page.locator('textarea[name="options"]').fill('size: XL, x-l');
+  await page.getByRole('button', { name: 'Add variants' }).click();
+
+  await expect(page.getByRole('main')).toContainText("value 'x-l' defined twice in option 'size'");
+});